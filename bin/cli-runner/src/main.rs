@@ -1,16 +1,74 @@
-use core::Generator as GeneratorTrait;
+use std::io::Write;
+
+use core::error::{Error, Result};
+use core::raster::Format;
+use core::theme::ColorScheme;
 use leetcode::{extension::Extension, Config, Generator};
 
+const USAGE: &str =
+    "usage: cli-runner <username> [--format svg|png|webp] [--scheme light|dark] [--output path]";
+
 #[tokio::main]
 async fn main() {
-    let config = Config::new("thibault-cne");
-    let animation = Extension::Animation;
-    let config = config.add_extension(animation);
+    let (config, output) = match parse_args(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}\n{USAGE}", e.hint());
+            std::process::exit(2);
+        }
+    };
 
-    let mut generator = Generator::new(config);
+    let mut generator = Generator::new(config.add_extension(Extension::Animation));
     generator.verbose();
 
-    let content = generator.generate().await.unwrap();
+    let content = match generator.generate_bytes().await {
+        Ok(content) => content,
+        Err(e) => {
+            eprintln!("{}: {e:?}", e.hint());
+            std::process::exit(1);
+        }
+    };
+
+    let written = match output {
+        Some(path) => std::fs::write(path, content),
+        None => std::io::stdout().write_all(&content),
+    };
+    if let Err(e) = written {
+        eprintln!("the card could not be written: {e}");
+        std::process::exit(1);
+    }
+}
+
+// Reads the card options and the output path, rejecting the same values as
+// the query parameters of the worker.
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<(Config, Option<String>)> {
+    let mut username = None;
+    let mut format = Format::Svg;
+    let mut scheme = ColorScheme::Light;
+    let mut output = None;
+
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next().ok_or_else(|| {
+                Error::new_invalid_parameter().with(format!("{arg} is missing its value"))
+            })
+        };
+        match arg.as_str() {
+            "--format" => format = Format::try_from(value()?.as_str())?,
+            "--scheme" => scheme = ColorScheme::try_from(value()?.as_str())?,
+            "--output" => output = Some(value()?),
+            flag if flag.starts_with("--") => {
+                return Err(Error::new_invalid_parameter().with(format!("unknown option {flag}")))
+            }
+            _ => username = Some(arg),
+        }
+    }
+
+    let username =
+        username.ok_or_else(|| Error::new_invalid_parameter().with("username is missing"))?;
+    let config = Config::new(&username)
+        .set_format(format)
+        .set_color_scheme(scheme);
 
-    println!("{}", content);
+    Ok((config, output))
 }
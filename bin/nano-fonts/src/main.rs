@@ -13,31 +13,44 @@ async fn main() {
         std::fs::create_dir("json").unwrap();
     }
 
+    let mut manifest = Vec::new();
+
     // Convert all fonts to WOFF2 and save them to `json/` directory
     for font in FONTS {
         let json_font = font.convert().unwrap();
-
-        let json = serde_json::to_string(&json_font).unwrap();
-
-        std::fs::write(
-            format!("json/{}.json", font.name.replace(' ', "_").to_lowercase()),
-            json,
-        )
-        .unwrap();
+        manifest.push(write_font(json_font));
     }
 
     // Download all Google Fonts and save them to `json/` directory
     for font in GoogleApiResponse::fetch().await.unwrap().items {
         if let Ok(json_font) = font.try_into_json().await {
-            let json = serde_json::to_string(&json_font).unwrap();
-
-            std::fs::write(
-                format!("json/{}.json", font.family.replace(' ', "_").to_lowercase()),
-                json,
-            )
-            .unwrap();
+            manifest.push(write_font(json_font));
         }
     }
+
+    // The manifest is read by `core::font` to list the available fonts.
+    manifest.sort_by(|a, b| a.family.cmp(&b.family));
+    manifest.dedup_by(|a, b| a.family == b.family);
+    let manifest = serde_json::to_string_pretty(&manifest).unwrap() + "\n";
+    std::fs::write("json/fonts.json", &manifest).unwrap();
+    std::fs::write("crates/core/fonts.json", &manifest).unwrap();
+}
+
+fn write_font(json_font: JsonFont) -> ManifestEntry {
+    let filename = json_font.name.replace(' ', "_").to_lowercase();
+    let json = serde_json::to_string(&json_font).unwrap();
+    std::fs::write(format!("json/{}.json", filename), json).unwrap();
+
+    ManifestEntry {
+        family: json_font.name,
+        filename,
+    }
+}
+
+#[derive(serde::Serialize)]
+struct ManifestEntry {
+    family: String,
+    filename: String,
 }
 
 #[derive(serde::Deserialize)]
@@ -62,21 +75,28 @@ struct GoogleFont {
     files: HashMap<String, String>,
 }
 
+// Google Fonts variants converted for every family, as named in `files`,
+// with their weight and style. Those are the ones drawn by the cards.
+const VARIANTS: &[(&str, u16, bool)] = &[("regular", 400, false), ("700", 700, false)];
+
 impl GoogleFont {
     async fn try_into_json(&self) -> Result<JsonFont> {
-        if let Some(regular) = self.files.get("regular") {
-            let ttf = reqwest::get(regular).await?.bytes().await?;
-            let woff2 =
-                woff2::convert_ttf_to_woff2(&ttf, &[], 11, true).map_err(|_| Error::Woff2)?;
-            let base64 = base64::engine::general_purpose::STANDARD.encode(woff2);
-
-            Ok(JsonFont {
-                name: self.family.clone(),
-                base64: format!("data:font/woff2;charset=utf-8;base64,{}", base64),
-            })
-        } else {
-            Err(Error::RegularFontNotFound)
+        if !self.files.contains_key("regular") {
+            return Err(Error::RegularFontNotFound);
+        }
+
+        let mut faces = Vec::new();
+        for (variant, weight, italic) in VARIANTS {
+            if let Some(url) = self.files.get(*variant) {
+                let ttf = reqwest::get(url).await?.bytes().await?;
+                faces.push(JsonFace::convert(&ttf, *weight, *italic)?);
+            }
         }
+
+        Ok(JsonFont {
+            name: self.family.clone(),
+            faces,
+        })
     }
 }
 
@@ -85,21 +105,42 @@ struct Font {
     filename: &'static str,
 }
 
-#[derive(serde::Deserialize, serde::Serialize)]
+#[derive(serde::Serialize)]
 struct JsonFont {
     pub name: String,
+    pub faces: Vec<JsonFace>,
+}
+
+#[derive(serde::Serialize)]
+struct JsonFace {
+    pub weight: u16,
+    pub italic: bool,
     pub base64: String,
+    // Raw TrueType source, used to rasterize and subset cards.
+    pub ttf: Option<String>,
+}
+
+impl JsonFace {
+    fn convert(ttf: &[u8], weight: u16, italic: bool) -> Result<JsonFace> {
+        let woff2 = woff2::convert_ttf_to_woff2(ttf, &[], 11, true).map_err(|_| Error::Woff2)?;
+        let base64 = base64::engine::general_purpose::STANDARD.encode(woff2);
+
+        Ok(JsonFace {
+            weight,
+            italic,
+            base64: format!("data:font/woff2;charset=utf-8;base64,{}", base64),
+            ttf: Some(base64::engine::general_purpose::STANDARD.encode(ttf)),
+        })
+    }
 }
 
 impl Font {
     fn convert(&self) -> Result<JsonFont> {
         let ttf = std::fs::read(format!("font/{}", self.filename))?;
-        let woff2 = woff2::convert_ttf_to_woff2(&ttf, &[], 11, true).map_err(|_| Error::Woff2)?;
-        let base64 = base64::engine::general_purpose::STANDARD.encode(woff2);
 
         Ok(JsonFont {
             name: self.name.to_string(),
-            base64: format!("data:font/woff2;charset=utf-8;base64,{}", base64),
+            faces: vec![JsonFace::convert(&ttf, 400, false)?],
         })
     }
 }
@@ -32,6 +32,11 @@ pub fn handler(attr: TokenStream, items: TokenStream) -> TokenStream {
 
             worker::console_log!("Cache miss");
             let mut resp = #inner_fn_name(#fn_args).await?;
+            // Error cards must not outlive the failure that produced them.
+            if resp.status_code() != 200 {
+                resp.headers_mut().set("Cache-Control", "no-store")?;
+                return Ok(resp);
+            }
             resp.headers_mut()
                 .set("Cache-Control", "public, max-age=3600")?;
             ctx.data.#cache_ident().put(&url, resp).await?;
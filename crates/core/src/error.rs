@@ -20,12 +20,19 @@ pub enum ErrorKind {
     InvalidDifficultyKind,
     ToStr,
     Unit,
+    Raster,
+    UnsupportedFormat,
+    InvalidParameter,
+    UserNotFound,
+    InvalidTheme,
+    Font,
 }
 
 #[derive(Debug)]
 pub enum Reqwest {
     ToStr,
     Default,
+    TooLarge,
 }
 
 impl Error {
@@ -43,6 +50,10 @@ impl Error {
         Error::new(ErrorKind::Reqwest(Reqwest::ToStr))
     }
 
+    pub fn new_reqwest_too_large() -> Error {
+        Error::new(ErrorKind::Reqwest(Reqwest::TooLarge))
+    }
+
     pub fn new_invalid_difficulty_kind() -> Error {
         Error::new(ErrorKind::InvalidDifficultyKind)
     }
@@ -51,19 +62,116 @@ impl Error {
         Error::new(ErrorKind::Unit)
     }
 
+    pub fn new_raster() -> Error {
+        Error::new(ErrorKind::Raster)
+    }
+
+    pub fn new_unsupported_format() -> Error {
+        Error::new(ErrorKind::UnsupportedFormat)
+    }
+
+    pub fn new_invalid_parameter() -> Error {
+        Error::new(ErrorKind::InvalidParameter)
+    }
+
+    pub fn new_user_not_found() -> Error {
+        Error::new(ErrorKind::UserNotFound)
+    }
+
+    pub fn new_invalid_theme() -> Error {
+        Error::new(ErrorKind::InvalidTheme)
+    }
+
+    pub fn new_font() -> Error {
+        Error::new(ErrorKind::Font)
+    }
+
     pub fn with<C: Into<Cause>>(mut self, cause: C) -> Error {
         self.inner.cause = Some(cause.into());
         self
     }
 
+    pub fn kind(&self) -> &ErrorKind {
+        &self.inner.kind
+    }
+
+    pub fn status(&self) -> u16 {
+        self.inner.kind.status()
+    }
+
+    // Line shown under the title of an error card. Parameter errors carry the
+    // offending parameter as their cause, other causes are implementation
+    // details and stay out of the card.
+    pub fn hint(&self) -> String {
+        match (&self.inner.kind, &self.inner.cause) {
+            (ErrorKind::InvalidParameter | ErrorKind::UnsupportedFormat, Some(cause)) => {
+                cause.to_string()
+            }
+            (kind, _) => kind.hint().to_string(),
+        }
+    }
+
     fn description(&self) -> &str {
         match self.inner.kind {
             ErrorKind::Reqwest(Reqwest::Default) => "reqwest error",
             ErrorKind::Reqwest(Reqwest::ToStr) => "reqwest to string error",
+            ErrorKind::Reqwest(Reqwest::TooLarge) => "reqwest response too large",
             ErrorKind::SerdeJson => "serde_json error",
             ErrorKind::InvalidDifficultyKind => "invalid difficulty kind",
             ErrorKind::ToStr => "to string error",
             ErrorKind::Unit => "unit error",
+            ErrorKind::Raster => "rasterization error",
+            ErrorKind::UnsupportedFormat => "unsupported output format",
+            ErrorKind::InvalidParameter => "invalid parameter",
+            ErrorKind::UserNotFound => "user not found",
+            ErrorKind::InvalidTheme => "invalid theme",
+            ErrorKind::Font => "font error",
+        }
+    }
+}
+
+impl ErrorKind {
+    pub fn status(&self) -> u16 {
+        match self {
+            ErrorKind::Reqwest(_)
+            | ErrorKind::SerdeJson
+            | ErrorKind::InvalidDifficultyKind
+            | ErrorKind::ToStr => 502,
+            ErrorKind::InvalidParameter | ErrorKind::UnsupportedFormat => 400,
+            ErrorKind::UserNotFound => 404,
+            ErrorKind::Unit | ErrorKind::Raster | ErrorKind::InvalidTheme | ErrorKind::Font => 500,
+        }
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            ErrorKind::Reqwest(_) | ErrorKind::ToStr => "LeetCode unavailable",
+            ErrorKind::SerdeJson | ErrorKind::InvalidDifficultyKind => {
+                "Unexpected LeetCode response"
+            }
+            ErrorKind::InvalidParameter => "Invalid parameter",
+            ErrorKind::UnsupportedFormat => "Unsupported format",
+            ErrorKind::UserNotFound => "User not found",
+            ErrorKind::Unit | ErrorKind::Raster | ErrorKind::InvalidTheme | ErrorKind::Font => {
+                "Something went wrong"
+            }
+        }
+    }
+
+    pub fn hint(&self) -> &'static str {
+        match self {
+            ErrorKind::Reqwest(_) | ErrorKind::ToStr => {
+                "LeetCode could not be reached, try again later"
+            }
+            ErrorKind::SerdeJson | ErrorKind::InvalidDifficultyKind => {
+                "LeetCode answered with data that could not be read"
+            }
+            ErrorKind::InvalidParameter => "Check the query parameters of the card",
+            ErrorKind::UnsupportedFormat => "Use one of svg, png or webp",
+            ErrorKind::UserNotFound => "No LeetCode user matches this username",
+            ErrorKind::Unit | ErrorKind::Raster | ErrorKind::InvalidTheme | ErrorKind::Font => {
+                "The card could not be generated"
+            }
         }
     }
 }
@@ -139,3 +247,26 @@ mod macros {
 
     pub(super) use error_from;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kinds_map_to_http_statuses() {
+        assert_eq!(Error::new_invalid_parameter().status(), 400);
+        assert_eq!(Error::new_unsupported_format().status(), 400);
+        assert_eq!(Error::new_reqwest_default().status(), 502);
+        assert_eq!(Error::new_raster().status(), 500);
+        assert_eq!(Error::new_user_not_found().status(), 404);
+    }
+
+    #[test]
+    fn only_parameter_causes_reach_the_card() {
+        let error = Error::new_invalid_parameter().with("width must be a positive integer");
+        assert_eq!(error.hint(), "width must be a positive integer");
+
+        let error = Error::new_reqwest_default().with("dns error: leetcode.com");
+        assert_eq!(error.hint(), ErrorKind::Reqwest(Reqwest::Default).hint());
+    }
+}
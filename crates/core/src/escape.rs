@@ -0,0 +1,105 @@
+use std::borrow::Cow;
+
+/// Escapes a string so it can be written as an XML text node.
+pub fn text(value: &str) -> Cow<'_, str> {
+    escape(value, |c| match c {
+        '&' => Some("&amp;"),
+        '<' => Some("&lt;"),
+        '>' => Some("&gt;"),
+        _ => None,
+    })
+}
+
+/// Escapes a string so it can be written inside a double (or single) quoted
+/// XML attribute value.
+pub fn attribute(value: &str) -> Cow<'_, str> {
+    escape(value, |c| match c {
+        '&' => Some("&amp;"),
+        '<' => Some("&lt;"),
+        '>' => Some("&gt;"),
+        '"' => Some("&quot;"),
+        '\'' => Some("&apos;"),
+        '\t' => Some("&#9;"),
+        '\n' => Some("&#10;"),
+        '\r' => Some("&#13;"),
+        _ => None,
+    })
+}
+
+/// Wraps a string in a CDATA section, splitting any `]]>` sequence so the
+/// section cannot be closed early.
+pub fn cdata(value: &str) -> String {
+    let value = strip_invalid(value);
+    format!("<![CDATA[{}]]>", value.replace("]]>", "]]]]><![CDATA[>"))
+}
+
+fn escape(value: &str, replacement: impl Fn(char) -> Option<&'static str>) -> Cow<'_, str> {
+    if !value
+        .chars()
+        .any(|c| replacement(c).is_some() || !is_valid_char(c))
+    {
+        return Cow::Borrowed(value);
+    }
+
+    let mut escaped = String::with_capacity(value.len() + 16);
+    for c in value.chars().filter(|c| is_valid_char(*c)) {
+        match replacement(c) {
+            Some(entity) => escaped.push_str(entity),
+            None => escaped.push(c),
+        }
+    }
+    Cow::Owned(escaped)
+}
+
+fn strip_invalid(value: &str) -> Cow<'_, str> {
+    if value.chars().all(is_valid_char) {
+        Cow::Borrowed(value)
+    } else {
+        Cow::Owned(value.chars().filter(|c| is_valid_char(*c)).collect())
+    }
+}
+
+// Characters allowed by the XML 1.0 `Char` production, anything else makes
+// the whole document unparsable.
+fn is_valid_char(c: char) -> bool {
+    matches!(c,
+        '\t' | '\n' | '\r'
+        | '\u{20}'..='\u{D7FF}'
+        | '\u{E000}'..='\u{FFFD}'
+        | '\u{10000}'..='\u{10FFFF}')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leaves_plain_strings_borrowed() {
+        assert!(matches!(text("thibault-cne"), Cow::Borrowed(_)));
+        assert!(matches!(attribute("#ranking"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn escapes_markup() {
+        assert_eq!(text("a < b && c > d"), "a &lt; b &amp;&amp; c &gt; d");
+        assert_eq!(
+            attribute(r#"" onload="alert('x')"#),
+            "&quot; onload=&quot;alert(&apos;x&apos;)"
+        );
+    }
+
+    #[test]
+    fn strips_invalid_characters() {
+        assert_eq!(text("bad\u{0}\u{1b}name\u{FFFF}"), "badname");
+        assert_eq!(attribute("a\u{8}b"), "ab");
+    }
+
+    #[test]
+    fn cdata_cannot_be_closed_early() {
+        assert_eq!(cdata("a{b:c}"), "<![CDATA[a{b:c}]]>");
+        assert_eq!(
+            cdata("x]]><script/>"),
+            "<![CDATA[x]]]]><![CDATA[><script/>]]>"
+        );
+    }
+}
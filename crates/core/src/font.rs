@@ -1,13 +1,87 @@
-#[derive(Debug, Clone, Copy)]
-pub enum Font {
-    Baloo2,
-    Formula1,
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::item::FontStyle;
+
+// A font family of the manifest written by `nano-fonts`, the filename is the
+// stem of its converted JSON file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Font {
+    family: &'static str,
+    filename: &'static str,
+}
+
+pub const BALOO_2: Font = Font {
+    family: "Baloo 2",
+    filename: "baloo_2",
+};
+pub const FORMULA_1: Font = Font {
+    family: "Formula 1",
+    filename: "formula_1",
+};
+pub const DEFAULT: Font = BALOO_2;
+
+const MANIFEST: &str = include_str!("../fonts.json");
+
+// Entry of the manifest, read into owned strings since family names may hold
+// JSON escapes.
+#[derive(Deserialize)]
+struct ManifestEntry {
+    family: String,
+    filename: String,
 }
 
+pub fn catalog() -> &'static [Font] {
+    static FONTS: OnceLock<Vec<Font>> = OnceLock::new();
+    FONTS.get_or_init(|| {
+        serde_json::from_str::<Vec<ManifestEntry>>(MANIFEST)
+            .expect("fonts.json is validated by the tests")
+            .into_iter()
+            // Read once for the whole process.
+            .map(|entry| Font {
+                family: Box::leak(entry.family.into_boxed_str()),
+                filename: Box::leak(entry.filename.into_boxed_str()),
+            })
+            .collect()
+    })
+}
+
+// Finds a font by family name, case insensitively, or by filename.
+pub fn get(name: &str) -> Option<Font> {
+    catalog()
+        .iter()
+        .find(|font| font.family.eq_ignore_ascii_case(name) || font.filename == name)
+        .copied()
+}
+
+// Fonts already loaded by this process, keyed by filename.
+static CACHE: OnceLock<Mutex<HashMap<&'static str, Arc<JsonFont>>>> = OnceLock::new();
+
 impl Font {
     const BASE_URL: &'static str = "https://cdn.jsdelivr.net/gh/thibault-cne/rustme@json/";
 
-    pub async fn fetch(&self) -> crate::error::Result<JsonFont> {
+    // Looks the font up in the cache, then in the fonts bundled at build time
+    // and only fetches it as a last resort.
+    pub async fn load(&self) -> Result<Arc<JsonFont>> {
+        let cache = CACHE.get_or_init(Default::default);
+        if let Some(font) = cache.lock().unwrap().get(self.filename()) {
+            return Ok(font.clone());
+        }
+
+        let font = match self.bundled() {
+            Some(ttf) => JsonFont::from_ttf(self.font_family(), ttf),
+            None => self.fetch().await?,
+        };
+        let font = Arc::new(font);
+        cache.lock().unwrap().insert(self.filename(), font.clone());
+
+        Ok(font)
+    }
+
+    pub async fn fetch(&self) -> Result<JsonFont> {
         let url = format!("{}{}.json", Self::BASE_URL, self.filename());
 
         let resp = reqwest::get(url).await?;
@@ -16,56 +90,457 @@ impl Font {
     }
 }
 
-#[derive(serde::Deserialize, serde::Serialize)]
+#[derive(Deserialize, Serialize)]
+#[serde(from = "JsonFontRepr")]
 pub struct JsonFont {
     pub name: String,
+    pub faces: Vec<JsonFace>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct JsonFace {
+    #[serde(default = "regular_weight")]
+    pub weight: u16,
+    #[serde(default)]
+    pub italic: bool,
     pub base64: String,
+    // Base64 encoded TrueType source, needed to rasterize and subset the face.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttf: Option<String>,
 }
 
-macros::impl_fonts!(
-    BALOO_2 => Baloo2, "baloo_2", "Baloo 2";
-    FORMULA_1 => Formula1, "formula_1", "Formula 1";
-);
+// Fonts converted before they had several faces hold a single regular one.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonFontRepr {
+    Faces {
+        name: String,
+        faces: Vec<JsonFace>,
+    },
+    Regular {
+        name: String,
+        #[serde(flatten)]
+        face: JsonFace,
+    },
+}
 
-mod macros {
-    macro_rules! impl_fonts {
-        ($($const:ident => $font:ident, $filename:literal, $font_family:literal);*;) => {
-            impl Font {
-                pub fn filename(&self) -> &'static str {
-                    match self {
-                        $(Font::$font => $filename),*
-                    }
-                }
+impl From<JsonFontRepr> for JsonFont {
+    fn from(repr: JsonFontRepr) -> Self {
+        match repr {
+            JsonFontRepr::Faces { name, faces } => JsonFont { name, faces },
+            JsonFontRepr::Regular { name, face } => JsonFont {
+                name,
+                faces: vec![face],
+            },
+        }
+    }
+}
 
-                pub fn font_family(&self) -> &'static str {
-                    match self {
-                        $(Font::$font => $font_family),*
-                    }
-                }
+fn regular_weight() -> u16 {
+    FontStyle::default().weight
+}
+
+impl JsonFont {
+    pub fn from_ttf(name: &str, ttf: &[u8]) -> JsonFont {
+        use base64::Engine;
+
+        let ttf = base64::engine::general_purpose::STANDARD.encode(ttf);
+        JsonFont {
+            name: name.to_string(),
+            faces: vec![JsonFace {
+                weight: regular_weight(),
+                italic: false,
+                base64: format!("data:font/ttf;base64,{}", ttf),
+                ttf: Some(ttf),
+            }],
+        }
+    }
+
+    // TrueType sources of every face.
+    pub fn ttf_bytes(&self) -> Vec<Vec<u8>> {
+        self.faces.iter().filter_map(JsonFace::ttf_bytes).collect()
+    }
+
+    // Face drawing text with `style`.
+    pub fn face(&self, style: FontStyle) -> Option<&JsonFace> {
+        self.face_for(style).map(|face| &self.faces[face])
+    }
+
+    // Index of the face closest to `style`, matching the style first and the
+    // weight then, as browsers do.
+    fn face_for(&self, style: FontStyle) -> Option<usize> {
+        self.faces
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, face)| {
+                (
+                    face.italic != style.italic,
+                    face.weight.abs_diff(style.weight),
+                )
+            })
+            .map(|(i, _)| i)
+    }
+
+    // One `@font-face` rule per face drawing some of the text, each embedding
+    // only the glyphs it draws.
+    pub fn font_faces(&self, runs: &BTreeMap<FontStyle, String>) -> Vec<String> {
+        let mut texts = BTreeMap::<usize, String>::new();
+        for (style, text) in runs {
+            if let Some(face) = self.face_for(*style) {
+                texts.entry(face).or_default().push_str(text);
             }
+        }
 
-            impl From<&str> for Font {
-                fn from(s: &str) -> Self {
-                    match s {
-                        $($filename | $font_family => Font::$font,)*
-                        _ => Font::Baloo2
-                    }
-                }
+        texts
+            .into_iter()
+            .map(|(face, text)| self.faces[face].font_face(&self.name, &text))
+            .collect()
+    }
+}
+
+impl JsonFace {
+    pub fn ttf_bytes(&self) -> Option<Vec<u8>> {
+        use base64::Engine;
+
+        self.ttf
+            .as_ref()
+            .and_then(|ttf| base64::engine::general_purpose::STANDARD.decode(ttf).ok())
+    }
+
+    // `@font-face` rule embedding only the glyphs needed to draw `text`. The
+    // whole face is embedded when it has no TrueType source or cannot be
+    // subset.
+    fn font_face(&self, name: &str, text: &str) -> String {
+        use base64::Engine;
+
+        let subset = self
+            .ttf_bytes()
+            .and_then(|ttf| crate::subset::subset(&ttf, text).ok());
+        let src = match subset {
+            Some(subset) => format!(
+                r#"url("data:font/ttf;base64,{}") format("truetype")"#,
+                base64::engine::general_purpose::STANDARD.encode(subset)
+            ),
+            None if self.base64.starts_with("data:font/ttf") => {
+                format!(r#"url("{}") format("truetype")"#, self.base64)
             }
+            None => format!(r#"url("{}") format("woff2")"#, self.base64),
+        };
+        let style = if self.italic { "italic" } else { "normal" };
+        format!(
+            r#"@font-face{{font-family:"{}";font-weight:{};font-style:{};src:{}}}"#,
+            name, self.weight, style, src
+        )
+    }
+}
+
+impl Font {
+    pub fn filename(&self) -> &'static str {
+        self.filename
+    }
 
-            impl From<String> for Font {
-                fn from(s: String) -> Self {
-                    s.as_str().into()
+    pub fn font_family(&self) -> &'static str {
+        self.family
+    }
+
+    // Fonts with a TrueType file in `font/` can be embedded in the build with
+    // the cargo feature named after them.
+    fn bundled(&self) -> Option<&'static [u8]> {
+        #[cfg(feature = "font-formula-1")]
+        if *self == FORMULA_1 {
+            return Some(include_bytes!("../../../font/Formula1-Regular-1.ttf"));
+        }
+        None
+    }
+}
+
+// The first font embedded in the build, used to rasterize a card when none of
+// its fonts comes with a TrueType source.
+pub fn bundled_fallback() -> Option<&'static [u8]> {
+    FORMULA_1.bundled()
+}
+
+// Unknown fonts fall back to the default one, use `get` to tell them apart.
+impl From<&str> for Font {
+    fn from(s: &str) -> Self {
+        get(s).unwrap_or(DEFAULT)
+    }
+}
+
+impl From<String> for Font {
+    fn from(s: String) -> Self {
+        s.as_str().into()
+    }
+}
+
+// CSS generic families, drawn with fonts of the viewer's system.
+const GENERIC_FAMILIES: [&str; 13] = [
+    "serif",
+    "sans-serif",
+    "monospace",
+    "cursive",
+    "fantasy",
+    "system-ui",
+    "ui-serif",
+    "ui-sans-serif",
+    "ui-monospace",
+    "ui-rounded",
+    "emoji",
+    "math",
+    "fangsong",
+];
+
+// Stack used by `system`, the native interface font of every major platform.
+const SYSTEM: [&str; 6] = [
+    "system-ui",
+    "-apple-system",
+    "\"Segoe UI\"",
+    "Roboto",
+    "\"Helvetica Neue\"",
+    "sans-serif",
+];
+
+// Ordered list of families a text is drawn with, the browser picks the first
+// one that has a glyph. Fonts of the catalog are embedded in the card, other
+// families are left to the viewer's system.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FontStack {
+    families: Vec<Family>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Family {
+    Font(Font),
+    System(&'static str),
+}
+
+impl FontStack {
+    // Accepts a comma separated list of fonts listed in the catalog, CSS
+    // generic families, and `system` for the native interface font.
+    pub fn parse(value: &str) -> Result<FontStack> {
+        let mut families = Vec::new();
+        for name in value.split(',').map(str::trim) {
+            match name {
+                "system" => families.extend(SYSTEM.map(Family::System)),
+                name => {
+                    let family = GENERIC_FAMILIES
+                        .iter()
+                        .find(|generic| generic.eq_ignore_ascii_case(name))
+                        .map(|generic| Family::System(generic))
+                        .or_else(|| get(name).map(Family::Font))
+                        .ok_or_else(|| {
+                            Error::new_invalid_parameter().with(format!(
+                                "\"{name}\" is neither a font listed at /fonts nor a generic family"
+                            ))
+                        })?;
+                    families.push(family);
                 }
             }
+        }
 
-            $(
-                pub const $const: Font = Font::$font;
-            )*
+        Ok(FontStack { families })
+    }
 
-            pub const ALL_FONTS: &[Font] = &[$($const),*];
-        };
+    // Stack embedding nothing, drawn with the native interface font.
+    pub fn system() -> FontStack {
+        FontStack {
+            families: SYSTEM.map(Family::System).to_vec(),
+        }
+    }
+
+    // Appends `font` to the stack, to be drawn when no family before it is.
+    pub fn with_fallback(mut self, font: Font) -> FontStack {
+        self.families.push(Family::Font(font));
+        self
+    }
+
+    // Fonts to load and embed in the card.
+    pub fn fonts(&self) -> impl Iterator<Item = Font> + '_ {
+        self.families.iter().filter_map(|family| match family {
+            Family::Font(font) => Some(*font),
+            Family::System(_) => None,
+        })
+    }
+
+    // Value of the `font-family` property.
+    pub fn css(&self) -> String {
+        self.families
+            .iter()
+            .map(|family| match family {
+                Family::Font(font) => format!("\"{}\"", font.font_family()),
+                Family::System(name) => name.to_string(),
+            })
+            .collect::<Vec<String>>()
+            .join(",")
+    }
+}
+
+impl From<Font> for FontStack {
+    fn from(font: Font) -> Self {
+        FontStack {
+            families: vec![Family::Font(font)],
+        }
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::Engine;
+
+    use super::*;
+    use crate::error::ErrorKind;
+
+    const BOLD: FontStyle = FontStyle {
+        weight: 700,
+        italic: false,
+    };
+
+    fn face(weight: u16, ttf: Option<&[u8]>) -> JsonFace {
+        JsonFace {
+            weight,
+            italic: false,
+            base64: "data:font/woff2;base64,d09GMgABAAAAAA".to_string(),
+            ttf: ttf.map(|ttf| base64::engine::general_purpose::STANDARD.encode(ttf)),
+        }
+    }
+
+    fn json_font(faces: Vec<JsonFace>) -> JsonFont {
+        JsonFont {
+            name: "Formula 1".to_string(),
+            faces,
+        }
+    }
+
+    fn runs(runs: &[(FontStyle, &str)]) -> BTreeMap<FontStyle, String> {
+        runs.iter()
+            .map(|(style, text)| (*style, text.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn manifest_lists_the_known_fonts() {
+        let fonts = catalog();
 
-    pub(super) use impl_fonts;
+        assert!(fonts.contains(&BALOO_2) && fonts.contains(&FORMULA_1));
+        assert!(fonts.windows(2).all(|w| w[0].family < w[1].family));
+        assert_eq!(get("formula 1"), Some(FORMULA_1));
+        assert_eq!(get("baloo_2"), Some(BALOO_2));
+        assert_eq!(get("Comic Sans"), None);
+        assert_eq!(Font::from("Comic Sans"), DEFAULT);
+    }
+
+    #[test]
+    fn font_stacks_mix_fonts_and_generic_families() {
+        let stack = FontStack::parse("formula 1, system-ui,Sans-Serif").unwrap();
+
+        assert_eq!(stack.css(), r#""Formula 1",system-ui,sans-serif"#);
+        assert_eq!(stack.fonts().collect::<Vec<Font>>(), [FORMULA_1]);
+        assert_eq!(FontStack::parse("system").unwrap(), FontStack::system());
+        assert_eq!(FontStack::system().fonts().count(), 0);
+        let fallback = FontStack::system().with_fallback(BALOO_2);
+        assert!(fallback.css().ends_with(r#"sans-serif,"Baloo 2""#));
+    }
+
+    #[test]
+    fn unknown_families_are_rejected() {
+        for value in ["Comic Sans", r#""Segoe UI""#, "x;}*{fill:red", "baloo_2,"] {
+            let error = FontStack::parse(value).unwrap_err();
+            assert!(
+                matches!(error.kind(), ErrorKind::InvalidParameter),
+                "{value}"
+            );
+        }
+    }
+
+    #[test]
+    fn escaped_family_names_are_read() {
+        let entries = serde_json::from_str::<Vec<ManifestEntry>>(
+            r#"[{"family":"Caf\u00e9 \"Sans\"","filename":"cafe_sans"}]"#,
+        )
+        .unwrap();
+
+        assert_eq!(entries[0].family, r#"Café "Sans""#);
+    }
+
+    #[test]
+    fn single_face_fonts_are_still_read() {
+        let font: JsonFont =
+            serde_json::from_str(r#"{"name":"Baloo 2","base64":"data:font/woff2;base64,AA"}"#)
+                .unwrap();
+
+        assert_eq!(font.faces.len(), 1);
+        assert_eq!((font.faces[0].weight, font.faces[0].italic), (400, false));
+    }
+
+    #[test]
+    fn font_face_embeds_a_subset() {
+        let ttf = include_bytes!("../../../font/Formula1-Regular-1.ttf");
+        let faces = json_font(vec![face(400, Some(ttf))])
+            .font_faces(&runs(&[(FontStyle::default(), "fixture 123,456")]));
+
+        assert_eq!(faces.len(), 1);
+        assert!(faces[0].starts_with(
+            r#"@font-face{font-family:"Formula 1";font-weight:400;font-style:normal;src:url("data:font/ttf;base64,"#
+        ));
+        assert!(faces[0].ends_with(r#"format("truetype")}"#));
+        assert!(faces[0].len() * 4 < ttf.len(), "{} bytes", faces[0].len());
+    }
+
+    #[test]
+    fn one_face_is_emitted_per_face_used() {
+        let font = json_font(vec![face(400, None), face(700, None)]);
+
+        let faces = font.font_faces(&runs(&[(FontStyle::default(), "Easy")]));
+        assert_eq!(faces.len(), 1);
+        assert!(faces[0].contains("font-weight:400"));
+
+        let faces = font.font_faces(&runs(&[(FontStyle::default(), "Easy"), (BOLD, "42")]));
+        assert_eq!(faces.len(), 2);
+        assert!(faces[1].contains("font-weight:700"));
+
+        // Without a bold face, bold text is drawn with the regular one.
+        let regular = json_font(vec![face(400, None)]);
+        assert_eq!(regular.font_faces(&runs(&[(BOLD, "42")])).len(), 1);
+    }
+
+    #[test]
+    fn bundled_ttf_fonts_fall_back_to_truetype() {
+        let faces = JsonFont::from_ttf("Formula 1", b"not a font")
+            .font_faces(&runs(&[(FontStyle::default(), "fixture")]));
+
+        assert_eq!(
+            faces,
+            [
+                r#"@font-face{font-family:"Formula 1";font-weight:400;font-style:normal;src:url("data:font/ttf;base64,bm90IGEgZm9udA==") format("truetype")}"#
+            ]
+        );
+    }
+
+    #[cfg(feature = "font-formula-1")]
+    #[test]
+    fn bundled_fonts_load_without_network() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let font = runtime.block_on(FORMULA_1.load()).unwrap();
+
+        assert_eq!(font.name, "Formula 1");
+        assert_eq!(font.ttf_bytes().len(), 1);
+        let cached = runtime.block_on(FORMULA_1.load()).unwrap();
+        assert!(Arc::ptr_eq(&font, &cached));
+    }
+
+    #[test]
+    fn font_face_falls_back_to_the_whole_face() {
+        let expected = [
+            r#"@font-face{font-family:"Formula 1";font-weight:400;font-style:normal;src:url("data:font/woff2;base64,d09GMgABAAAAAA") format("woff2")}"#,
+        ];
+        let runs = runs(&[(FontStyle::default(), "fixture")]);
+
+        assert_eq!(json_font(vec![face(400, None)]).font_faces(&runs), expected);
+        assert_eq!(
+            json_font(vec![face(400, Some(b"not a font"))]).font_faces(&runs),
+            expected
+        );
+    }
 }
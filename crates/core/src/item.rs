@@ -1,4 +1,6 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
+
+use crate::escape;
 
 pub struct ItemBuilder {
     counter: u32,
@@ -17,10 +19,16 @@ impl ItemBuilder {
         let attr = item
             .attr
             .iter()
-            .map(|(k, v)| format!(r#"{}="{}""#, k, v))
+            .map(|(k, v)| format!(r#"{}="{}""#, k, escape::attribute(&v.to_string())))
             .collect::<Vec<String>>()
             .join(" ");
 
+        let content = match item.content.as_ref() {
+            Some(Content::Text(text)) => escape::text(text).into_owned(),
+            Some(Content::Raw(raw)) => escape::cdata(raw),
+            None => String::new(),
+        };
+
         let children = item
             .children
             .as_mut()
@@ -37,11 +45,7 @@ impl ItemBuilder {
             Some(true) => format!("<{} {}/>", item.item_type, attr),
             _ => format!(
                 "<{} {}>{}{}</{}>",
-                item.item_type,
-                attr,
-                item.content.as_ref().unwrap_or(&"".to_string()),
-                children,
-                item.item_type
+                item.item_type, attr, content, children, item.item_type
             ),
         }
     }
@@ -101,36 +105,226 @@ impl Default for ItemBuilder {
 
 pub struct Item {
     item_type: String,
-    attr: HashMap<String, Attribute>,
+    attr: Attributes,
     style: Vec<(String, String)>,
     single: Option<bool>,
     children: Option<Vec<Item>>,
-    content: Option<String>,
+    content: Option<Content>,
 }
 
-impl Item {
-    pub fn new(
-        item_type: &str,
-        attr: Option<HashMap<String, Attribute>>,
-        style: Option<Vec<(String, String)>>,
-        single: Option<bool>,
-        children: Option<Vec<Item>>,
-        content: Option<String>,
-    ) -> Item {
-        let item_type = item_type.to_string();
-        let attr = attr.unwrap_or_default();
-        let style = style.unwrap_or_default();
+// Weight and style of the font face text is drawn with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FontStyle {
+    pub weight: u16,
+    pub italic: bool,
+}
+
+impl Default for FontStyle {
+    fn default() -> Self {
+        FontStyle {
+            weight: 400,
+            italic: false,
+        }
+    }
+}
 
+pub enum Content {
+    // Escaped when serialized.
+    Text(String),
+    // Written verbatim inside a CDATA section, for `<style>` and alike.
+    Raw(String),
+}
+
+impl Item {
+    pub fn new(item_type: &str) -> Item {
         Item {
-            item_type,
-            attr,
-            style,
-            single,
-            children,
-            content,
+            item_type: item_type.to_string(),
+            attr: Attributes::new(),
+            style: Vec::new(),
+            single: None,
+            children: None,
+            content: None,
         }
     }
 
+    fn single(item_type: &str) -> Item {
+        let mut item = Item::new(item_type);
+        item.single = Some(true);
+        item
+    }
+
+    pub fn svg(width: u32, height: u32) -> Item {
+        Item::new("svg")
+            .attr("width", width)
+            .attr("height", height)
+            .attr("viewBox", format!("0 0 {} {}", width, height))
+            .attr("version", "1.1")
+            .attr("xmlns", "http://www.w3.org/2000/svg")
+            .attr("xmlns:xlink", "http://www.w3.org/1999/xlink")
+    }
+
+    pub fn g() -> Item {
+        Item::new("g")
+    }
+
+    pub fn a(href: &str) -> Item {
+        Item::new("a").attr("href", href).attr("target", "_blank")
+    }
+
+    pub fn title(content: impl Into<String>) -> Item {
+        Item::new("title").content(content)
+    }
+
+    pub fn text(content: impl Into<String>) -> Item {
+        Item::new("text").content(content)
+    }
+
+    pub fn rect(x: f64, y: f64, width: f64, height: f64) -> Item {
+        Item::single("rect")
+            .attr("x", x)
+            .attr("y", y)
+            .attr("width", width)
+            .attr("height", height)
+    }
+
+    pub fn circle(cx: f64, cy: f64, r: f64) -> Item {
+        Item::single("circle")
+            .attr("cx", cx)
+            .attr("cy", cy)
+            .attr("r", r)
+    }
+
+    pub fn line(x1: f64, y1: f64, x2: f64, y2: f64) -> Item {
+        Item::single("line")
+            .attr("x1", x1)
+            .attr("y1", y1)
+            .attr("x2", x2)
+            .attr("y2", y2)
+    }
+
+    pub fn path(d: &str) -> Item {
+        Item::single("path").attr("d", d)
+    }
+
+    // `href` is best a data URI, a card shown as an `<img>` loads nothing else.
+    pub fn image(x: f64, y: f64, width: f64, height: f64, href: &str) -> Item {
+        Item::single("image")
+            .attr("x", x)
+            .attr("y", y)
+            .attr("width", width)
+            .attr("height", height)
+            .attr("href", href)
+    }
+
+    pub fn defs() -> Item {
+        Item::new("defs")
+    }
+
+    pub fn stop(offset: f64, color: &str) -> Item {
+        Item::single("stop")
+            .attr("offset", offset)
+            .attr("stop-color", color)
+    }
+
+    pub fn stylesheet(content: String) -> Item {
+        let mut item = Item::new("style");
+        item.content = Some(Content::Raw(content));
+        item
+    }
+
+    pub fn id(self, id: &str) -> Item {
+        self.attr("id", id)
+    }
+
+    pub fn attr(mut self, key: &str, value: impl Into<Attribute>) -> Item {
+        self.attr.insert(key.to_string(), value.into());
+        self
+    }
+
+    pub fn style(mut self, style: Vec<(String, String)>) -> Item {
+        self.style.extend(style);
+        self
+    }
+
+    pub fn content(mut self, content: impl Into<String>) -> Item {
+        self.content = Some(Content::Text(content.into()));
+        self
+    }
+
+    pub fn raw(mut self) -> Item {
+        self.content = self.content.map(|content| match content {
+            Content::Text(text) | Content::Raw(text) => Content::Raw(text),
+        });
+        self
+    }
+
+    pub fn child(mut self, child: Item) -> Item {
+        self.push_child(child);
+        self
+    }
+
+    pub fn children(mut self, children: impl IntoIterator<Item = Item>) -> Item {
+        children
+            .into_iter()
+            .for_each(|child| self.push_child(child));
+        self
+    }
+
+    // Text drawn by the item and its children grouped by the `font-family`
+    // set on them, if any, and by the font face it is drawn with. Raw content
+    // such as a stylesheet is not drawn and left out.
+    pub fn text_runs(&self) -> BTreeMap<(Option<String>, FontStyle), String> {
+        let mut runs = BTreeMap::new();
+        self.collect_text(None, FontStyle::default(), &mut runs);
+        runs
+    }
+
+    fn collect_text(
+        &self,
+        family: Option<&str>,
+        inherited: FontStyle,
+        runs: &mut BTreeMap<(Option<String>, FontStyle), String>,
+    ) {
+        let family = self
+            .style
+            .iter()
+            .rev()
+            .find(|(key, _)| key == "font-family")
+            .map(|(_, value)| value.as_str())
+            .or(family);
+        let style = self.style.iter().fold(inherited, |style, (key, value)| {
+            match (key.as_str(), value.as_str()) {
+                ("font-weight", "normal") => FontStyle {
+                    weight: 400,
+                    ..style
+                },
+                ("font-weight", "bold" | "bolder") => FontStyle {
+                    weight: 700,
+                    ..style
+                },
+                ("font-weight", weight) => FontStyle {
+                    weight: weight.parse().unwrap_or(style.weight),
+                    ..style
+                },
+                ("font-style", font_style) => FontStyle {
+                    italic: font_style != "normal",
+                    ..style
+                },
+                _ => style,
+            }
+        });
+
+        if let Some(Content::Text(content)) = self.content.as_ref() {
+            runs.entry((family.map(str::to_string), style))
+                .or_default()
+                .push_str(content);
+        }
+        self.children
+            .iter()
+            .flatten()
+            .for_each(|child| child.collect_text(family, style, runs));
+    }
+
     pub fn push_child(&mut self, child: Item) {
         if let Some(children) = self.children.as_mut() {
             children.push(child)
@@ -139,15 +333,126 @@ impl Item {
         }
     }
 
-    pub fn style(content: String) -> Item {
-        Item::new("style", None, None, None, None, Some(content))
+    pub fn translate(self, x: f64, y: f64) -> Item {
+        self.transform(format!("translate({} {})", x, y))
+    }
+
+    pub fn rotate(self, angle: f64, cx: f64, cy: f64) -> Item {
+        self.transform(format!("rotate({} {} {})", angle, cx, cy))
+    }
+
+    pub fn scale(self, factor: f64) -> Item {
+        self.transform(format!("scale({})", factor))
+    }
+
+    fn transform(mut self, transform: String) -> Item {
+        match self.attr.get_mut("transform") {
+            Some(previous) => {
+                *previous = Attribute::from(format!("{} {}", previous, transform));
+                self
+            }
+            None => self.attr("transform", transform),
+        }
+    }
+
+    pub fn fill(self, paint: &str) -> Item {
+        self.style(vec![("fill".to_string(), paint.to_string())])
+    }
+
+    pub fn stroke(self, paint: &str, width: f64) -> Item {
+        self.style(vec![
+            ("stroke".to_string(), paint.to_string()),
+            ("stroke-width".to_string(), format!("{}px", width)),
+        ])
+    }
+
+    pub fn font(self, size: u32, bold: bool) -> Item {
+        let item = self.style(vec![("font-size".to_string(), format!("{}px", size))]);
+        if bold {
+            item.style(vec![("font-weight".to_string(), "bold".to_string())])
+        } else {
+            item
+        }
+    }
+
+    pub fn font_family(self, family: &str) -> Item {
+        self.style(vec![("font-family".to_string(), family.to_string())])
+    }
+
+    pub fn anchor(self, anchor: &str) -> Item {
+        self.style(vec![("text-anchor".to_string(), anchor.to_string())])
+    }
+}
+
+// Attributes keep their insertion order so the same tree always serializes
+// to the same bytes.
+#[derive(Default)]
+pub struct Attributes {
+    entries: Vec<(String, Attribute)>,
+}
+
+impl Attributes {
+    pub fn new() -> Attributes {
+        Attributes::default()
+    }
+
+    pub fn insert(&mut self, key: String, value: Attribute) -> Option<Attribute> {
+        match self.get_mut(&key) {
+            Some(previous) => Some(std::mem::replace(previous, value)),
+            None => {
+                self.entries.push((key, value));
+                None
+            }
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Attribute> {
+        self.entries.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Attribute> {
+        self.entries
+            .iter_mut()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v)
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<Attribute> {
+        let index = self.entries.iter().position(|(k, _)| k == key)?;
+        Some(self.entries.remove(index).1)
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Attribute)> {
+        self.entries.iter().map(|(k, v)| (k, v))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl FromIterator<(String, Attribute)> for Attributes {
+    fn from_iter<T: IntoIterator<Item = (String, Attribute)>>(iter: T) -> Self {
+        let mut attributes = Attributes::new();
+        iter.into_iter().for_each(|(k, v)| {
+            attributes.insert(k, v);
+        });
+        attributes
     }
 }
 
 pub enum Attribute {
     String(String),
     Array(Vec<String>),
-    Map(HashMap<String, Attribute>),
+    Map(Attributes),
 }
 
 impl std::fmt::Display for Attribute {
@@ -166,12 +471,12 @@ impl std::fmt::Display for Attribute {
 
 impl From<Vec<(String, Attribute)>> for Attribute {
     fn from(value: Vec<(String, Attribute)>) -> Self {
-        Attribute::Map(HashMap::from_iter(value))
+        Attribute::Map(Attributes::from_iter(value))
     }
 }
 
-impl From<HashMap<String, Attribute>> for Attribute {
-    fn from(value: HashMap<String, Attribute>) -> Self {
+impl From<Attributes> for Attribute {
+    fn from(value: Attributes) -> Self {
         Attribute::Map(value)
     }
 }
@@ -193,3 +498,152 @@ impl From<String> for Attribute {
         Attribute::String(value)
     }
 }
+
+macros::attribute_from_number!(u32, i32, f64);
+
+mod macros {
+    macro_rules! attribute_from_number {
+        ($($number:ty),*) => {
+            $(
+                impl From<$number> for Attribute {
+                    fn from(value: $number) -> Self {
+                        Attribute::String(value.to_string())
+                    }
+                }
+            )*
+        };
+    }
+
+    pub(super) use attribute_from_number;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOSTILE: &[&str] = &[
+        "<script>alert(1)</script>",
+        r#"" onload="alert(1)"#,
+        "Tom & Jerry",
+        "]]><svg onload=alert(1)>",
+        "O'Brien <o'brien@example.com>",
+    ];
+
+    fn unescape(value: &str) -> String {
+        value
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&apos;", "'")
+            .replace("&#9;", "\t")
+            .replace("&#10;", "\n")
+            .replace("&#13;", "\r")
+            .replace("&amp;", "&")
+    }
+
+    fn text(content: &str) -> Item {
+        Item::text(content).id("t").attr("data-name", content)
+    }
+
+    #[test]
+    fn text_runs_follow_inherited_font_styles() {
+        let item = Item::g()
+            .child(Item::text("Easy"))
+            .child(Item::g().font(16, true).child(Item::text("42")))
+            .child(Item::text("/800"))
+            .child(Item::g().font_family("mono").child(Item::text("7")))
+            .child(Item::stylesheet("*{fill:red}".to_string()));
+        let bold = FontStyle {
+            weight: 700,
+            italic: false,
+        };
+
+        let runs = item.text_runs();
+        assert_eq!(runs.len(), 3);
+        assert_eq!(runs[&(None, FontStyle::default())], "Easy/800");
+        assert_eq!(runs[&(None, bold)], "42");
+        assert_eq!(runs[&(Some("mono".to_string()), FontStyle::default())], "7");
+    }
+
+    #[test]
+    fn text_content_round_trips() {
+        for hostile in HOSTILE {
+            let svg = ItemBuilder::default().stringify(&mut text(hostile));
+            let inner = svg
+                .split_once('>')
+                .and_then(|(_, rest)| rest.strip_suffix("</text>"))
+                .unwrap();
+
+            assert!(!inner.contains('<'), "{svg}");
+            assert_eq!(unescape(inner), *hostile);
+        }
+    }
+
+    #[test]
+    fn attribute_values_round_trip() {
+        for hostile in HOSTILE {
+            let svg = ItemBuilder::default().stringify(&mut text(hostile));
+            let value = svg
+                .split_once(r#"data-name=""#)
+                .and_then(|(_, rest)| rest.split_once('"'))
+                .map(|(value, _)| value)
+                .unwrap();
+
+            assert!(!value.contains(['<', '"']), "{svg}");
+            assert_eq!(unescape(value), *hostile);
+        }
+    }
+
+    #[test]
+    fn attributes_keep_insertion_order() {
+        let mut item = Item::new("rect")
+            .attr("z", 1)
+            .attr("a", 2)
+            .attr("m", 3)
+            .attr("a", 4)
+            .id("r");
+        let svg = ItemBuilder::default().stringify(&mut item);
+
+        assert_eq!(svg, r#"<rect z="1" a="4" m="3" id="r"></rect>"#);
+    }
+
+    #[test]
+    fn stringify_is_deterministic() {
+        fn tree() -> Item {
+            Item::svg(500, 200)
+                .id("root")
+                .child(
+                    Item::g()
+                        .translate(10.0, 20.0)
+                        .rotate(-90.0, 40.0, 40.0)
+                        .style(crate::style! { "fill": "red", "stroke": "blue" })
+                        .children((0..32).map(|i| {
+                            Item::circle(i as f64, 2.0 * i as f64, 3.0).attr("data-index", i)
+                        })),
+                )
+                .child(Item::text("generated ids").fill("var(--text-0)"))
+        }
+
+        let render = || {
+            let mut builder = ItemBuilder::default();
+            let mut root = tree();
+            let css = builder.css(&mut root);
+            root.push_child(Item::stylesheet(css));
+            builder.stringify(&mut root)
+        };
+
+        let first = render();
+        assert!(first.starts_with(r#"<svg width="500" height="200" viewBox="0 0 500 200""#));
+        for _ in 0..8 {
+            assert_eq!(render(), first);
+        }
+    }
+
+    #[test]
+    fn style_content_is_kept_raw() {
+        let css = r#"@font-face{font-family:"Baloo 2"}#a>b{fill:#fff}"#;
+        let svg = ItemBuilder::default().stringify(&mut Item::stylesheet(css.to_string()));
+
+        assert!(svg.contains(&format!("<![CDATA[{css}]]>")), "{svg}");
+    }
+}
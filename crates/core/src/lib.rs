@@ -1,7 +1,12 @@
 pub mod error;
+pub mod escape;
 pub mod font;
 pub mod item;
 pub mod macros;
+pub mod metrics;
+pub mod paint;
+pub mod raster;
+pub mod subset;
 pub mod theme;
 
 use item::Item;
@@ -128,12 +128,12 @@ macro_rules! attribute {
     };
 
     ({}) => {
-        $crate::item::Attribute::from(HashMap::<String, $crate::item::Attribute>::new())
+        $crate::item::Attribute::from($crate::item::Attributes::new())
     };
 
     ({ $($tt:tt)+ }) => {
         {
-            let mut map = std::collections::HashMap::<String, $crate::item::Attribute>::new();
+            let mut map = $crate::item::Attributes::new();
             attribute!(@map map () ($($tt)+) ($($tt)+));
             map
         }
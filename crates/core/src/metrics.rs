@@ -0,0 +1,138 @@
+use crate::error::{Error, Result};
+
+const ELLIPSIS: char = '…';
+
+// Measures text drawn with a font. Without the TrueType source, e.g. for a
+// system font, widths are estimated from the shape of each character.
+#[derive(Default)]
+pub struct Metrics {
+    ttf: Option<Vec<u8>>,
+}
+
+impl Metrics {
+    pub fn new(ttf: Vec<u8>) -> Result<Metrics> {
+        ttf_parser::Face::parse(&ttf, 0).map_err(|e| Error::new_font().with(e))?;
+        Ok(Metrics { ttf: Some(ttf) })
+    }
+
+    // Estimated metrics, for text drawn with fonts of the viewer's system.
+    pub fn approximate() -> Metrics {
+        Metrics::default()
+    }
+
+    // Width of `text` drawn at `size` pixels, kerning is not applied.
+    pub fn width(&self, text: &str, size: f64) -> f64 {
+        let face = self
+            .ttf
+            .as_ref()
+            .and_then(|ttf| ttf_parser::Face::parse(ttf, 0).ok());
+        let em = text
+            .chars()
+            .map(|c| {
+                face.as_ref()
+                    .and_then(|face| {
+                        let advance = face.glyph_hor_advance(face.glyph_index(c)?)?;
+                        Some(advance as f64 / face.units_per_em() as f64)
+                    })
+                    // Browsers draw missing glyphs with a fallback font.
+                    .unwrap_or_else(|| approximate_advance(c))
+            })
+            .sum::<f64>();
+
+        em * size
+    }
+
+    // Fits `text` in `max_width` pixels, shrinking it down to `min_size` and
+    // truncating it with an ellipsis past that. Returns the text to draw and
+    // its size.
+    pub fn fit(&self, text: &str, size: f64, min_size: f64, max_width: f64) -> (String, f64) {
+        let width = self.width(text, size);
+        if width <= max_width {
+            return (text.to_string(), size);
+        }
+        let shrunk = (size * max_width / width).floor();
+        if shrunk >= min_size {
+            return (text.to_string(), shrunk);
+        }
+
+        let mut chars = text.chars().collect::<Vec<char>>();
+        while !chars.is_empty() {
+            chars.pop();
+            let truncated = format!("{}{ELLIPSIS}", chars.iter().collect::<String>().trim_end());
+            if self.width(&truncated, min_size) <= max_width {
+                return (truncated, min_size);
+            }
+        }
+
+        (ELLIPSIS.to_string(), min_size)
+    }
+}
+
+// Advance of `c` in ems, tuned on common bold sans-serif fonts.
+fn approximate_advance(c: char) -> f64 {
+    match c {
+        ' ' => 0.28,
+        'i' | 'j' | 'l' | 'I' | '.' | ',' | ':' | ';' | '\'' | '|' | '!' => 0.3,
+        '(' | ')' | '[' | ']' | '{' | '}' | 'f' | 't' | 'r' => 0.38,
+        'm' | 'w' | 'M' | 'W' | '@' => 0.88,
+        'A'..='Z' => 0.68,
+        '0'..='9' => 0.58,
+        c if c.is_ascii() => 0.56,
+        // CJK, full width forms and emoji take a whole em.
+        '\u{1100}'..='\u{115F}' | '\u{2E80}'..='\u{A4CF}' | '\u{AC00}'..='\u{D7A3}' => 1.0,
+        '\u{F900}'..='\u{FAFF}' | '\u{FE30}'..='\u{FE4F}' | '\u{FF00}'..='\u{FF60}' => 1.0,
+        '\u{1F300}'..='\u{1FAFF}' | '\u{20000}'..='\u{3FFFD}' => 1.0,
+        _ => 0.6,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FONT: &[u8] = include_bytes!("../../../font/Formula1-Regular-1.ttf");
+
+    #[test]
+    fn widths_follow_the_font_advances() {
+        let metrics = Metrics::new(FONT.to_vec()).unwrap();
+
+        let narrow = metrics.width("iiii", 24.0);
+        let wide = metrics.width("MMMM", 24.0);
+        assert!(narrow < wide);
+        assert_eq!(metrics.width("MMMM", 48.0), wide * 2.0);
+        assert_eq!(metrics.width("", 24.0), 0.0);
+    }
+
+    #[test]
+    fn wide_characters_are_estimated_wider() {
+        let metrics = Metrics::approximate();
+
+        assert!(metrics.width("名前", 24.0) > metrics.width("ab", 24.0));
+        assert_eq!(metrics.width("名前", 24.0), 48.0);
+    }
+
+    #[test]
+    fn text_is_shrunk_then_truncated() {
+        let metrics = Metrics::approximate();
+
+        assert_eq!(
+            metrics.fit("fixture", 24.0, 16.0, 400.0),
+            ("fixture".to_string(), 24.0)
+        );
+
+        let (text, size) = metrics.fit("fixture", 24.0, 16.0, 60.0);
+        assert_eq!(text, "fixture");
+        assert!((16.0..24.0).contains(&size));
+        assert!(metrics.width(&text, size) <= 60.0);
+
+        let (text, size) = metrics.fit("a rather long username", 24.0, 16.0, 100.0);
+        assert!(text.ends_with(ELLIPSIS) && text.len() < "a rather long username".len());
+        assert_eq!(size, 16.0);
+        assert!(metrics.width(&text, size) <= 100.0);
+    }
+
+    #[test]
+    fn invalid_fonts_are_rejected() {
+        assert!(Metrics::new(b"not a font".to_vec()).is_err());
+    }
+}
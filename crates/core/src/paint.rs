@@ -0,0 +1,279 @@
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::error::{Error, Result};
+use crate::item::Item;
+
+// How a theme variable is painted. Anything but a plain color is defined once
+// in the `<defs>` of the card and referenced with `url(#id)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Paint {
+    kind: Kind,
+    stops: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Color,
+    // Angle in degrees, following CSS: 0 points up and 90 to the right.
+    Linear(f64),
+    Radial,
+    Stripes(f64),
+    Dots,
+}
+
+impl Paint {
+    // Accepts a CSS color, `linear-gradient([angle,] colors...)`,
+    // `radial-gradient(colors...)`, `stripes([angle,] base, stripe)` or
+    // `dots(base, dot)`.
+    pub fn parse(value: &str) -> Result<Paint> {
+        let value = value.trim();
+        let (function, arguments) = match value.strip_suffix(')').and_then(|v| v.split_once('(')) {
+            Some((function, arguments)) if FUNCTIONS.contains(&function.trim()) => {
+                (function.trim(), split_arguments(arguments))
+            }
+            _ => {
+                return Ok(Paint {
+                    kind: Kind::Color,
+                    stops: vec![parse_color(value)?],
+                })
+            }
+        };
+
+        let (angle, colors) = match arguments.split_first() {
+            Some((first, rest)) if first.trim().ends_with("deg") => {
+                let angle = first.trim().trim_end_matches("deg").parse::<f64>();
+                (Some(angle.map_err(|_| invalid(first))?), rest)
+            }
+            _ => (None, arguments.as_slice()),
+        };
+        let stops = colors
+            .iter()
+            .map(|color| parse_color(color))
+            .collect::<Result<Vec<String>>>()?;
+
+        let kind = match (function, angle) {
+            ("linear-gradient", angle) => Kind::Linear(angle.unwrap_or(180.0)),
+            ("radial-gradient", None) => Kind::Radial,
+            ("stripes", angle) => Kind::Stripes(angle.unwrap_or(45.0)),
+            ("dots", None) => Kind::Dots,
+            _ => return Err(invalid(value)),
+        };
+        let expected = match kind {
+            Kind::Stripes(_) | Kind::Dots => stops.len() == 2,
+            _ => stops.len() >= 2,
+        };
+        if !expected {
+            return Err(invalid(value));
+        }
+
+        Ok(Paint { kind, stops })
+    }
+
+    pub fn is_color(&self) -> bool {
+        self.kind == Kind::Color
+    }
+
+    // Colors of the paint, the first one stands for the whole paint when a
+    // single color is needed.
+    pub fn stops(&self) -> &[String] {
+        &self.stops
+    }
+
+    pub fn map_stops(&self, f: impl FnMut(&String) -> String) -> Paint {
+        Paint {
+            kind: self.kind,
+            stops: self.stops.iter().map(f).collect(),
+        }
+    }
+
+    // Value of the CSS property, `id` is the one of the definition.
+    pub fn css(&self, id: &str) -> String {
+        match self.kind {
+            Kind::Color => self.stops[0].clone(),
+            _ => format!("url(#{id})"),
+        }
+    }
+
+    // Gradients span a `width` by `height` card in its own coordinates, the
+    // bounding box of a straight `<line>` has no height to stretch them over.
+    pub fn definition(&self, id: &str, width: f64, height: f64) -> Option<Item> {
+        let stops = || {
+            let last = (self.stops.len() - 1) as f64;
+            self.stops
+                .iter()
+                .enumerate()
+                .map(move |(i, color)| Item::stop(i as f64 / last, color))
+        };
+
+        let new = |item_type: &str| Item::new(item_type).id(id);
+        let item = match self.kind {
+            Kind::Color => return None,
+            Kind::Linear(angle) => {
+                let (sin, cos) = angle.to_radians().sin_cos();
+                // As in CSS, the gradient line runs through the center and
+                // its ends meet the perpendiculars through the corners.
+                let half = (width * sin.abs() + height * cos.abs()) / 2.0;
+                let (dx, dy) = (sin * half, -cos * half);
+                new("linearGradient")
+                    .attr("gradientUnits", "userSpaceOnUse")
+                    .attr("x1", round(width / 2.0 - dx))
+                    .attr("y1", round(height / 2.0 - dy))
+                    .attr("x2", round(width / 2.0 + dx))
+                    .attr("y2", round(height / 2.0 + dy))
+                    .children(stops())
+            }
+            Kind::Radial => new("radialGradient")
+                .attr("gradientUnits", "userSpaceOnUse")
+                .attr("cx", round(width / 2.0))
+                .attr("cy", round(height / 2.0))
+                .attr("r", round(width.hypot(height) / 2.0))
+                .children(stops()),
+            Kind::Stripes(angle) => pattern(new("pattern"), 8.0)
+                .attr("patternTransform", format!("rotate({})", angle))
+                .child(Item::rect(0.0, 0.0, 8.0, 8.0).attr("fill", self.stops[0].as_str()))
+                .child(Item::rect(0.0, 0.0, 4.0, 8.0).attr("fill", self.stops[1].as_str())),
+            Kind::Dots => pattern(new("pattern"), 10.0)
+                .child(Item::rect(0.0, 0.0, 10.0, 10.0).attr("fill", self.stops[0].as_str()))
+                .child(Item::circle(5.0, 5.0, 2.0).attr("fill", self.stops[1].as_str())),
+        };
+
+        Some(item)
+    }
+}
+
+impl fmt::Display for Paint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let stops = self.stops.join(", ");
+        match self.kind {
+            Kind::Color => f.write_str(&stops),
+            Kind::Linear(angle) => write!(f, "linear-gradient({angle}deg, {stops})"),
+            Kind::Radial => write!(f, "radial-gradient({stops})"),
+            Kind::Stripes(angle) => write!(f, "stripes({angle}deg, {stops})"),
+            Kind::Dots => write!(f, "dots({stops})"),
+        }
+    }
+}
+
+impl Serialize for Paint {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+const FUNCTIONS: [&str; 4] = ["linear-gradient", "radial-gradient", "stripes", "dots"];
+
+fn pattern(item: Item, size: f64) -> Item {
+    item.attr("patternUnits", "userSpaceOnUse")
+        .attr("width", size)
+        .attr("height", size)
+}
+
+fn round(value: f64) -> f64 {
+    (value * 1000.0).round() / 1000.0 + 0.0
+}
+
+fn invalid(value: &str) -> Error {
+    Error::new_invalid_parameter().with(format!("\"{}\" is not a valid paint", value.trim()))
+}
+
+// Bare hex digits are accepted as a color since `#` has to be escaped in URLs.
+fn parse_color(value: &str) -> Result<String> {
+    let value = value.trim();
+    let value = if value.len() >= 3 && value.chars().all(|c| c.is_ascii_hexdigit()) {
+        format!("#{value}")
+    } else {
+        value.to_string()
+    };
+    let color = csscolorparser::parse(&value).map_err(|_| invalid(&value))?;
+    // Other notations are written back as hex, the stylesheet is minimized by
+    // stripping its spaces which would break `rgb(0 0 0)` and alike.
+    match value.starts_with('#') {
+        true => Ok(value.to_ascii_lowercase()),
+        false => Ok(color.to_css_hex()),
+    }
+}
+
+// Splits on the commas that are not nested in parentheses, so that
+// `rgb(0, 0, 0)` stays a single argument.
+pub fn split_arguments(value: &str) -> Vec<&str> {
+    let mut arguments = Vec::new();
+    let (mut depth, mut start) = (0_i32, 0);
+    for (i, c) in value.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                arguments.push(&value[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    arguments.push(&value[start..]);
+
+    arguments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colors_and_gradients_are_parsed() {
+        assert_eq!(Paint::parse("1A1B26").unwrap().to_string(), "#1a1b26");
+        assert_eq!(
+            Paint::parse("linear-gradient(135deg, #ff0000, rgb(0, 0, 255))")
+                .unwrap()
+                .to_string(),
+            "linear-gradient(135deg, #ff0000, #0000ff)"
+        );
+        assert_eq!(
+            Paint::parse("radial-gradient(fff,000)").unwrap().stops(),
+            ["#fff", "#000"]
+        );
+        assert_eq!(
+            Paint::parse("linear-gradient(hsl(120 50% 50%), rgb(0 0 0), teal)")
+                .unwrap()
+                .stops(),
+            ["#40bf40", "#000000", "#008080"]
+        );
+        assert!(Paint::parse("stripes(#fff, #eee)").is_ok());
+        assert!(Paint::parse("dots(#fff, #eee)").is_ok());
+    }
+
+    #[test]
+    fn invalid_paints_are_rejected() {
+        for value in [
+            "linear-gradient(#fff)",
+            "linear-gradient(up, #fff, #000)",
+            "radial-gradient(90deg, #fff, #000)",
+            "dots(#fff, #eee, #ddd)",
+            "conic-gradient(#fff, #000)",
+            "linear-gradient(#fff, red;})",
+        ] {
+            assert!(Paint::parse(value).is_err(), "{value}");
+        }
+    }
+
+    #[test]
+    fn only_non_color_paints_are_defined() {
+        let color = Paint::parse("#fff").unwrap();
+        assert_eq!(color.css("light-bg-0"), "#fff");
+        assert!(color.definition("light-bg-0", 500.0, 200.0).is_none());
+
+        let gradient = Paint::parse("linear-gradient(90deg, #fff, #000)").unwrap();
+        assert_eq!(gradient.css("light-bg-0"), "url(#light-bg-0)");
+
+        let mut definition = gradient.definition("light-bg-0", 500.0, 200.0).unwrap();
+        let svg = crate::item::ItemBuilder::default().stringify(&mut definition);
+        assert_eq!(
+            svg,
+            r##"<linearGradient id="light-bg-0" gradientUnits="userSpaceOnUse" x1="0" y1="100" x2="500" y2="100"><stop offset="0" stop-color="#fff" id="_1"/> <stop offset="1" stop-color="#000" id="_2"/></linearGradient>"##
+        );
+    }
+}
@@ -0,0 +1,241 @@
+use std::collections::HashMap;
+
+use crate::error::{Error, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Svg,
+    Png,
+    WebP,
+}
+
+impl Format {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Format::Svg => "image/svg+xml",
+            Format::Png => "image/png",
+            Format::WebP => "image/webp",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Svg => "svg",
+            Format::Png => "png",
+            Format::WebP => "webp",
+        }
+    }
+}
+
+impl TryFrom<&str> for Format {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "svg" => Ok(Format::Svg),
+            "png" => Ok(Format::Png),
+            "webp" => Ok(Format::WebP),
+            _ => Err(Error::new_unsupported_format()),
+        }
+    }
+}
+
+// Renders a generated card to a bitmap. `fonts` are raw TrueType/OpenType
+// files, the first one is used when a `font-family` cannot be matched.
+#[cfg(feature = "raster")]
+pub fn render(svg: &str, fonts: &[Vec<u8>], format: Format) -> Result<Vec<u8>> {
+    use resvg::{tiny_skia, usvg};
+
+    if format == Format::Svg {
+        return Ok(svg.as_bytes().to_vec());
+    }
+
+    // Fonts converted before their TrueType source was published leave none
+    // to draw with, the text is then drawn with a bundled font if any, as it
+    // would be missing from the card otherwise.
+    let fallback = crate::font::bundled_fallback().filter(|_| fonts.is_empty());
+    if fonts.is_empty() && fallback.is_none() && svg.contains("<text") {
+        return Err(Error::new_font().with("no TrueType font to draw the text of the card"));
+    }
+
+    let svg = resolve_variables(svg);
+
+    let mut options = usvg::Options::default();
+    let fontdb = options.fontdb_mut();
+    for font in fonts.iter().map(Vec::as_slice).chain(fallback) {
+        fontdb.load_font_data(font.to_vec());
+    }
+    let family = fontdb
+        .faces()
+        .next()
+        .and_then(|face| face.families.first())
+        .map(|family| family.0.clone());
+    // Families that are not loaded, such as the system fonts of a stack,
+    // resolve to a generic family, which is drawn with the first font.
+    if let Some(family) = family {
+        fontdb.set_serif_family(family.clone());
+        fontdb.set_sans_serif_family(family.clone());
+        fontdb.set_monospace_family(family.clone());
+        fontdb.set_cursive_family(family.clone());
+        fontdb.set_fantasy_family(family.clone());
+        options.font_family = family;
+    }
+
+    let tree = usvg::Tree::from_str(&svg, &options).map_err(|e| Error::new_raster().with(e))?;
+    let size = tree.size().to_int_size();
+    let mut pixmap =
+        tiny_skia::Pixmap::new(size.width(), size.height()).ok_or_else(Error::new_raster)?;
+    resvg::render(&tree, tiny_skia::Transform::default(), &mut pixmap.as_mut());
+
+    match format {
+        Format::WebP => encode_webp(&pixmap),
+        _ => pixmap.encode_png().map_err(|e| Error::new_raster().with(e)),
+    }
+}
+
+#[cfg(not(feature = "raster"))]
+pub fn render(svg: &str, _: &[Vec<u8>], format: Format) -> Result<Vec<u8>> {
+    match format {
+        Format::Svg => Ok(svg.as_bytes().to_vec()),
+        _ => Err(Error::new_unsupported_format()),
+    }
+}
+
+#[cfg(all(feature = "raster", feature = "webp"))]
+fn encode_webp(pixmap: &resvg::tiny_skia::Pixmap) -> Result<Vec<u8>> {
+    // tiny-skia stores premultiplied colors, WebP expects straight alpha.
+    let data = pixmap
+        .pixels()
+        .iter()
+        .flat_map(|pixel| {
+            let color = pixel.demultiply();
+            [color.red(), color.green(), color.blue(), color.alpha()]
+        })
+        .collect::<Vec<u8>>();
+
+    let mut bytes = Vec::new();
+    image_webp::WebPEncoder::new(&mut bytes)
+        .encode(
+            &data,
+            pixmap.width(),
+            pixmap.height(),
+            image_webp::ColorType::Rgba8,
+        )
+        .map_err(|e| Error::new_raster().with(e))?;
+    Ok(bytes)
+}
+
+#[cfg(all(feature = "raster", not(feature = "webp")))]
+fn encode_webp(_: &resvg::tiny_skia::Pixmap) -> Result<Vec<u8>> {
+    Err(Error::new_unsupported_format())
+}
+
+// The renderer does not understand CSS custom properties, so every
+// `var(--name)` is replaced by the last value declared for `--name`.
+pub fn resolve_variables(svg: &str) -> String {
+    let mut variables = HashMap::new();
+    let mut rest = svg;
+    while let Some(start) = rest.find("--") {
+        let declaration = &rest[start..];
+        let is_declaration = start > 0 && matches!(rest.as_bytes()[start - 1], b'{' | b';');
+        let end = declaration.find([';', '}']).unwrap_or(declaration.len());
+        if is_declaration {
+            if let Some((name, value)) = declaration[..end].split_once(':') {
+                variables.insert(name.trim(), value.trim());
+            }
+        }
+        rest = &declaration[2..];
+    }
+
+    let mut resolved = String::with_capacity(svg.len());
+    let mut rest = svg;
+    while let Some(start) = rest.find("var(") {
+        resolved.push_str(&rest[..start]);
+        let call = &rest[start..];
+        let end = match call.find(')') {
+            Some(end) => end,
+            None => break,
+        };
+        let (name, fallback) = match call[4..end].split_once(',') {
+            Some((name, fallback)) => (name.trim(), Some(fallback.trim())),
+            None => (call[4..end].trim(), None),
+        };
+        match variables.get(name).copied().or(fallback) {
+            Some(value) => resolved.push_str(value),
+            None => resolved.push_str(&call[..=end]),
+        }
+        rest = &call[end + 1..];
+    }
+    resolved.push_str(rest);
+
+    resolved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(all(feature = "raster", not(feature = "font-formula-1")))]
+    use crate::error::ErrorKind;
+
+    #[test]
+    fn variables_are_resolved_with_last_declaration() {
+        let svg = "<style>:root{--bg-0:#fff;--text-0:#000}:root{--bg-0:#101010}#a{fill:var(--bg-0);stroke:var(--text-0)}#b{fill:var(--missing, red)}</style>";
+
+        assert_eq!(
+            resolve_variables(svg),
+            "<style>:root{--bg-0:#fff;--text-0:#000}:root{--bg-0:#101010}#a{fill:#101010;stroke:#000}#b{fill:red}</style>"
+        );
+    }
+
+    #[test]
+    fn unknown_formats_are_rejected() {
+        assert_eq!(Format::try_from("PNG").unwrap(), Format::Png);
+        assert!(Format::try_from("gif").is_err());
+    }
+
+    #[cfg(feature = "raster")]
+    #[test]
+    fn renders_png() {
+        let svg = r##"<svg width="20" height="10" viewBox="0 0 20 10" xmlns="http://www.w3.org/2000/svg"><style><![CDATA[:root{--bg-0:#ff0000}#r{fill:var(--bg-0)}]]></style><rect id="r" x="0" y="0" width="20" height="10"/></svg>"##;
+        let png = render(svg, &[], Format::Png).unwrap();
+
+        assert_eq!(&png[1..4], b"PNG");
+    }
+
+    #[cfg(all(feature = "raster", not(feature = "font-formula-1")))]
+    #[test]
+    fn text_without_fonts_is_an_error() {
+        let svg = r#"<svg width="20" height="10" viewBox="0 0 20 10" xmlns="http://www.w3.org/2000/svg"><text y="8">a</text></svg>"#;
+
+        assert!(matches!(
+            render(svg, &[], Format::Png).unwrap_err().kind(),
+            ErrorKind::Font
+        ));
+    }
+
+    #[cfg(all(feature = "raster", feature = "font-formula-1"))]
+    #[test]
+    fn text_without_fonts_is_drawn_with_a_bundled_one() {
+        let svg = r#"<svg width="40" height="20" viewBox="0 0 40 20" xmlns="http://www.w3.org/2000/svg"><text y="16" font-family="Baloo 2" font-size="16">ab</text></svg>"#;
+        let png = render(svg, &[], Format::Png).unwrap();
+
+        let blank = render(r#"<svg width="40" height="20" viewBox="0 0 40 20" xmlns="http://www.w3.org/2000/svg"/>"#, &[], Format::Png).unwrap();
+        assert_ne!(png, blank);
+    }
+
+    #[cfg(feature = "raster")]
+    #[test]
+    fn renders_gradient_fills() {
+        let svg = r##"<svg width="20" height="10" viewBox="0 0 20 10" xmlns="http://www.w3.org/2000/svg"><defs><linearGradient id="g" x1="0" y1="0.5" x2="1" y2="0.5"><stop offset="0" stop-color="#ff0000"/><stop offset="1" stop-color="#0000ff"/></linearGradient></defs><style><![CDATA[:root{--bg-0:url(#g)}#r{fill:var(--bg-0)}]]></style><rect id="r" x="0" y="0" width="20" height="10"/></svg>"##;
+        let png = render(svg, &[], Format::Png).unwrap();
+        let options = resvg::usvg::Options::default();
+        let tree = resvg::usvg::Tree::from_str(&resolve_variables(svg), &options).unwrap();
+
+        assert_eq!(&png[1..4], b"PNG");
+        assert!(matches!(
+            tree.root().children()[0],
+            resvg::usvg::Node::Path(ref path)
+                if matches!(path.fill().unwrap().paint(), resvg::usvg::Paint::LinearGradient(_))
+        ));
+    }
+}
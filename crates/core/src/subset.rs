@@ -0,0 +1,358 @@
+use std::collections::BTreeSet;
+
+use crate::error::{Error, Result};
+
+// Tables copied from the source font. Glyph ids are kept, so these stay valid
+// as they are; `cmap`, `glyf`, `loca` and `post` are rebuilt. OpenType layout
+// tables (GPOS, GSUB, ...) are dropped: they are often larger than the glyphs
+// a card uses and only bring kerning and ligatures to a few short labels.
+// The hinting programs (`fpgm`, `prep`) and their `cvt ` values go together.
+const KEPT_TABLES: [&[u8; 4]; 11] = [
+    b"OS/2", b"cvt ", b"fpgm", b"gasp", b"head", b"hhea", b"hmtx", b"kern", b"maxp", b"name",
+    b"prep",
+];
+
+// Composite glyph flags, see the `glyf` table specification.
+const ARG_1_AND_2_ARE_WORDS: u16 = 0x0001;
+const WE_HAVE_A_SCALE: u16 = 0x0008;
+const MORE_COMPONENTS: u16 = 0x0020;
+const WE_HAVE_AN_X_AND_Y_SCALE: u16 = 0x0040;
+const WE_HAVE_A_TWO_BY_TWO: u16 = 0x0080;
+
+// Reduces a TrueType font to the glyphs needed to draw `text`. Outlines of
+// every other glyph are emptied and the character map only covers `text`,
+// which usually shrinks a font by an order of magnitude.
+pub fn subset(font: &[u8], text: &str) -> Result<Vec<u8>> {
+    let face = ttf_parser::Face::parse(font, 0).map_err(|e| Error::new_font().with(e))?;
+    let tables = Tables::parse(font)?;
+    let (glyf, loca) = match (tables.get(b"glyf"), tables.get(b"loca")) {
+        (Some(glyf), Some(loca)) => (glyf, loca),
+        // CFF outlines are not supported.
+        _ => return Err(Error::new_font().with("font has no glyf table")),
+    };
+    let head = tables.get(b"head").ok_or_else(Error::new_font)?;
+    let long_offsets = read_u16(head, 50)? == 1;
+    let num_glyphs = face.number_of_glyphs();
+
+    let glyph = |id: u16| -> Result<&[u8]> {
+        let (start, end) = if long_offsets {
+            let at = id as usize * 4;
+            (
+                read_u32(loca, at)? as usize,
+                read_u32(loca, at + 4)? as usize,
+            )
+        } else {
+            let at = id as usize * 2;
+            (
+                read_u16(loca, at)? as usize * 2,
+                read_u16(loca, at + 2)? as usize * 2,
+            )
+        };
+        glyf.get(start..end).ok_or_else(Error::new_font)
+    };
+
+    let mapping = text
+        .chars()
+        .collect::<BTreeSet<char>>()
+        .into_iter()
+        .filter_map(|c| Some((c, face.glyph_index(c)?.0)))
+        .collect::<Vec<(char, u16)>>();
+
+    // `.notdef` is always kept, components of composite glyphs are added as
+    // they are found.
+    let mut kept = BTreeSet::from([0]);
+    let mut pending = mapping.iter().map(|(_, id)| *id).collect::<Vec<u16>>();
+    while let Some(id) = pending.pop() {
+        if id >= num_glyphs || !kept.insert(id) {
+            continue;
+        }
+        pending.extend(components(glyph(id)?)?);
+    }
+
+    let mut new_glyf = Vec::new();
+    let mut new_loca = Vec::with_capacity((num_glyphs as usize + 1) * 4);
+    for id in 0..num_glyphs {
+        new_loca.extend((new_glyf.len() as u32).to_be_bytes());
+        if kept.contains(&id) {
+            new_glyf.extend_from_slice(glyph(id)?);
+            pad(&mut new_glyf);
+        }
+    }
+    new_loca.extend((new_glyf.len() as u32).to_be_bytes());
+
+    let mut new_head = head.to_vec();
+    // Offsets are written as 32 bit, the checksum adjustment is set last.
+    new_head
+        .get_mut(50..52)
+        .ok_or_else(Error::new_font)?
+        .copy_from_slice(&1_u16.to_be_bytes());
+    new_head
+        .get_mut(8..12)
+        .ok_or_else(Error::new_font)?
+        .copy_from_slice(&[0; 4]);
+
+    let mut new_tables = vec![
+        (*b"cmap", cmap(&mapping)),
+        (*b"glyf", new_glyf),
+        (*b"head", new_head),
+        (*b"loca", new_loca),
+    ];
+    // Version 3 of `post` drops the glyph names.
+    if let Some(post) = tables.get(b"post").and_then(|post| post.get(..32)) {
+        let mut post = post.to_vec();
+        post[..4].copy_from_slice(&0x0003_0000_u32.to_be_bytes());
+        new_tables.push((*b"post", post));
+    }
+    for tag in KEPT_TABLES.into_iter().filter(|tag| *tag != b"head") {
+        if let Some(table) = tables.get(tag) {
+            new_tables.push((*tag, table.to_vec()));
+        }
+    }
+    new_tables.sort_by_key(|(tag, _)| *tag);
+
+    Ok(write(new_tables))
+}
+
+struct Tables<'a> {
+    data: &'a [u8],
+    records: Vec<([u8; 4], usize, usize)>,
+}
+
+impl<'a> Tables<'a> {
+    fn parse(data: &'a [u8]) -> Result<Tables<'a>> {
+        let count = read_u16(data, 4)? as usize;
+        let records = (0..count)
+            .map(|i| {
+                let at = 12 + i * 16;
+                let tag = data.get(at..at + 4).ok_or_else(Error::new_font)?;
+                let offset = read_u32(data, at + 8)? as usize;
+                let length = read_u32(data, at + 12)? as usize;
+                Ok(([tag[0], tag[1], tag[2], tag[3]], offset, length))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Tables { data, records })
+    }
+
+    fn get(&self, tag: &[u8; 4]) -> Option<&'a [u8]> {
+        self.records
+            .iter()
+            .find(|(t, _, _)| t == tag)
+            .and_then(|(_, offset, length)| self.data.get(*offset..offset + length))
+    }
+}
+
+fn components(glyph: &[u8]) -> Result<Vec<u16>> {
+    // Simple glyphs have a positive number of contours.
+    if glyph.is_empty() || (read_u16(glyph, 0)? as i16) >= 0 {
+        return Ok(Vec::new());
+    }
+
+    let mut components = Vec::new();
+    let mut at = 10;
+    loop {
+        let flags = read_u16(glyph, at)?;
+        components.push(read_u16(glyph, at + 2)?);
+        at += 4;
+        at += if flags & ARG_1_AND_2_ARE_WORDS != 0 {
+            4
+        } else {
+            2
+        };
+        at += if flags & WE_HAVE_A_SCALE != 0 {
+            2
+        } else if flags & WE_HAVE_AN_X_AND_Y_SCALE != 0 {
+            4
+        } else if flags & WE_HAVE_A_TWO_BY_TWO != 0 {
+            8
+        } else {
+            0
+        };
+        if flags & MORE_COMPONENTS == 0 {
+            return Ok(components);
+        }
+    }
+}
+
+// Format 4 subtable for the Windows Unicode BMP encoding, consecutive
+// characters mapped to consecutive glyphs share a segment.
+fn cmap(mapping: &[(char, u16)]) -> Vec<u8> {
+    let mut segments: Vec<(u16, u16, u16)> = Vec::new();
+    for (c, id) in mapping.iter().filter(|(c, _)| (*c as u32) < 0xFFFF) {
+        let c = *c as u16;
+        match segments.last_mut() {
+            // Glyph ids close to `u16::MAX` cannot extend a segment.
+            Some((start, end, first))
+                if *end + 1 == c && first.checked_add(c - *start) == Some(*id) =>
+            {
+                *end = c
+            }
+            _ => segments.push((c, c, *id)),
+        }
+    }
+    segments.push((0xFFFF, 0xFFFF, 0));
+
+    let count = segments.len() as u16;
+    let search_range = 2 * (1 << (15 - count.leading_zeros()));
+    let entry_selector = 15 - count.leading_zeros() as u16;
+    let length = 16 + 8 * count;
+
+    let mut table = Vec::new();
+    for value in [0, 1, 3, 1] {
+        table.extend(u16::to_be_bytes(value));
+    }
+    table.extend(12_u32.to_be_bytes());
+    for value in [4, length, 0, count * 2, search_range, entry_selector] {
+        table.extend(u16::to_be_bytes(value));
+    }
+    table.extend(u16::to_be_bytes(count * 2 - search_range));
+    segments
+        .iter()
+        .for_each(|(_, end, _)| table.extend(end.to_be_bytes()));
+    table.extend(0_u16.to_be_bytes());
+    segments
+        .iter()
+        .for_each(|(start, _, _)| table.extend(start.to_be_bytes()));
+    for (start, _, id) in &segments {
+        // The last segment maps 0xFFFF to the missing glyph.
+        let delta = if *start == 0xFFFF {
+            1
+        } else {
+            id.wrapping_sub(*start)
+        };
+        table.extend(delta.to_be_bytes());
+    }
+    segments
+        .iter()
+        .for_each(|_| table.extend(0_u16.to_be_bytes()));
+
+    table
+}
+
+fn write(tables: Vec<([u8; 4], Vec<u8>)>) -> Vec<u8> {
+    let count = tables.len() as u16;
+    let entry_selector = 15 - count.leading_zeros() as u16;
+    let search_range = 16 * (1 << entry_selector);
+
+    let mut font = Vec::new();
+    font.extend(0x0001_0000_u32.to_be_bytes());
+    for value in [
+        count,
+        search_range,
+        entry_selector,
+        count * 16 - search_range,
+    ] {
+        font.extend(value.to_be_bytes());
+    }
+
+    let mut offset = 12 + 16 * tables.len();
+    for (tag, table) in &tables {
+        font.extend(tag);
+        font.extend(checksum(table).to_be_bytes());
+        font.extend((offset as u32).to_be_bytes());
+        font.extend((table.len() as u32).to_be_bytes());
+        offset += (table.len() + 3) & !3;
+    }
+    let mut head = None;
+    for (tag, table) in &tables {
+        if tag == b"head" {
+            head = Some(font.len());
+        }
+        font.extend(table);
+        pad(&mut font);
+    }
+
+    if let Some(head) = head {
+        let adjustment = 0xB1B0_AFBA_u32.wrapping_sub(checksum(&font));
+        font[head + 8..head + 12].copy_from_slice(&adjustment.to_be_bytes());
+    }
+
+    font
+}
+
+fn checksum(data: &[u8]) -> u32 {
+    data.chunks(4).fold(0_u32, |sum, chunk| {
+        let mut word = [0; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(word))
+    })
+}
+
+fn pad(data: &mut Vec<u8>) {
+    data.resize((data.len() + 3) & !3, 0);
+}
+
+fn read_u16(data: &[u8], at: usize) -> Result<u16> {
+    data.get(at..at + 2)
+        .map(|b| u16::from_be_bytes([b[0], b[1]]))
+        .ok_or_else(|| Error::new_font().with("unexpected end of font"))
+}
+
+fn read_u32(data: &[u8], at: usize) -> Result<u32> {
+    data.get(at..at + 4)
+        .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or_else(|| Error::new_font().with("unexpected end of font"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FONT: &[u8] = include_bytes!("../../../font/Formula1-Regular-1.ttf");
+
+    #[test]
+    fn keeps_only_the_glyphs_of_the_text() {
+        let subset = subset(FONT, "Easy 30 / 800").unwrap();
+        let face = ttf_parser::Face::parse(&subset, 0).unwrap();
+        let original = ttf_parser::Face::parse(FONT, 0).unwrap();
+
+        assert!(subset.len() * 4 < FONT.len(), "{} bytes", subset.len());
+        assert_eq!(face.number_of_glyphs(), original.number_of_glyphs());
+        for c in "Easy30/8".chars() {
+            let id = face.glyph_index(c).unwrap();
+            assert_eq!(Some(id), original.glyph_index(c));
+            assert_eq!(
+                face.glyph_bounding_box(id),
+                original.glyph_bounding_box(id),
+                "{c}"
+            );
+            assert_eq!(face.glyph_hor_advance(id), original.glyph_hor_advance(id));
+        }
+        assert!(face.glyph_index('Z').is_none());
+        let z = original.glyph_index('Z').unwrap();
+        assert!(face.glyph_bounding_box(z).is_none());
+    }
+
+    #[test]
+    fn hinting_tables_are_kept() {
+        let subset = subset(FONT, "fixture").unwrap();
+        let face = ttf_parser::RawFace::parse(&subset, 0).unwrap();
+        let original = ttf_parser::RawFace::parse(FONT, 0).unwrap();
+
+        for tag in [b"cvt ", b"fpgm", b"prep"] {
+            let tag = ttf_parser::Tag::from_bytes(tag);
+            assert_eq!(face.table(tag), original.table(tag), "{tag}");
+        }
+    }
+
+    #[test]
+    fn checksums_are_consistent() {
+        let subset = subset(FONT, "fixture").unwrap();
+
+        assert_eq!(subset.len() % 4, 0);
+        assert_eq!(checksum(&subset), 0xB1B0_AFBA);
+    }
+
+    #[test]
+    fn glyph_ids_at_the_end_of_the_range_start_new_segments() {
+        let table = cmap(&[('a', u16::MAX), ('b', 0)]);
+        let count = u16::from_be_bytes([table[18], table[19]]) / 2;
+
+        assert_eq!(count, 3);
+    }
+
+    #[test]
+    fn rejects_invalid_fonts() {
+        assert!(subset(b"not a font", "a").is_err());
+    }
+}
@@ -1,20 +1,38 @@
+use std::collections::BTreeMap;
+use std::error::Error as _;
+use std::sync::OnceLock;
+
+use csscolorparser::Color;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::item::Item;
+use crate::paint::{self, Paint};
+
 #[derive(Debug, Clone)]
 pub struct Theme {
     pub prefered_color_scheme: &'static str,
-    pub colors: &'static [Variable],
-    pub style: &'static str,
+    pub colors: Vec<Variable>,
+    pub style: String,
 }
 
 impl Theme {
-    const fn new(
-        prefered_color_scheme: &'static str,
-        colors: &'static [Variable],
-        style: &'static str,
-    ) -> Theme {
-        Theme {
-            prefered_color_scheme,
-            colors,
-            style,
+    // Overrides (or adds) a single color. `name` must be one of the variables
+    // used by the cards and `value` a valid paint.
+    pub fn set_color(&mut self, name: &str, value: &str) -> Result<()> {
+        self.merge(Variable::parse(name, value)?);
+        Ok(())
+    }
+
+    pub fn with_colors(mut self, colors: &[Variable]) -> Theme {
+        colors.iter().cloned().for_each(|color| self.merge(color));
+        self
+    }
+
+    fn merge(&mut self, variable: Variable) {
+        match self.colors.iter_mut().find(|c| c.name == variable.name) {
+            Some(color) => *color = variable,
+            None => self.colors.push(variable),
         }
     }
 
@@ -26,120 +44,678 @@ impl Theme {
         self.prefered_color_scheme = "dark";
     }
 
+    pub fn set_no_preference(&mut self) {
+        self.prefered_color_scheme = "no-preference";
+    }
+
     pub fn format_vars(&self) -> Vec<String> {
-        let mut vars: Vec<String> = self
+        let mut vars = self
             .colors
             .iter()
-            .take(self.colors.len() - 1)
-            .map(|c| format!("{}:{};", c.name, c.value))
+            .map(|c| format!("{}:{};", c.name, c.value.css(&self.paint_id(&c.name))))
             .collect::<Vec<String>>();
-        vars.push(format!(
-            "{}:{}",
-            self.colors.last().unwrap().name,
-            self.colors.last().unwrap().value
-        ));
+        if let Some(last) = vars.last_mut() {
+            last.pop();
+        }
 
         vars
     }
+
+    // Gradients and patterns referenced by the variables, to be placed in the
+    // `<defs>` of a `width` by `height` card.
+    pub fn defs(&self, width: f64, height: f64) -> Vec<Item> {
+        self.colors
+            .iter()
+            .filter_map(|c| c.value.definition(&self.paint_id(&c.name), width, height))
+            .collect()
+    }
+
+    fn paint_id(&self, name: &str) -> String {
+        format!(
+            "{}-{}",
+            self.prefered_color_scheme,
+            name.trim_start_matches("--")
+        )
+    }
+
+    pub fn paint(&self, name: &str) -> Option<&Paint> {
+        self.colors
+            .iter()
+            .find(|c| c.name == name)
+            .map(|c| &c.value)
+    }
+
+    // The color standing for the variable, the first stop of gradients and
+    // patterns.
+    pub fn color(&self, name: &str) -> Option<&str> {
+        self.paint(name).map(|paint| paint.stops()[0].as_str())
+    }
+
+    // WCAG contrast ratio between two of the theme variables, from 1 to 21.
+    // Gradients and patterns are judged on their least contrasted stops.
+    pub fn contrast(&self, foreground: &str, background: &str) -> Option<f64> {
+        let colors = |name| {
+            self.paint(name).map(|paint| {
+                paint
+                    .stops()
+                    .iter()
+                    .filter_map(|c| csscolorparser::parse(c).ok())
+            })
+        };
+        let backgrounds = colors(background)?.collect::<Vec<Color>>();
+
+        colors(foreground)?
+            .flat_map(|fg| backgrounds.iter().map(move |bg| contrast(&fg, bg)))
+            .reduce(f64::min)
+    }
+
+    fn parse_color(&self, name: &str) -> Option<Color> {
+        csscolorparser::parse(self.color(name)?).ok()
+    }
+
+    // Whether the theme is meant for a light or a dark environment, judged
+    // on the side of mid gray its background is.
+    pub fn scheme(&self) -> ColorScheme {
+        match self.parse_color("--bg-0") {
+            Some(background) if luminance(&background) < MID_GRAY => ColorScheme::Dark,
+            _ => ColorScheme::Light,
+        }
+    }
+
+    // Derives the theme for the opposite color scheme. The background
+    // lightness is mirrored in OKLab, every other color keeps its hue and
+    // chroma and gets the lightness that preserves its contrast against the
+    // background. Accent colors are kept as long as they stay visible.
+    pub fn counterpart(&self) -> Theme {
+        let background = match self.parse_color("--bg-0") {
+            Some(background) => background,
+            None => return self.clone(),
+        };
+        let derived = mirror(&background, self.scheme());
+
+        let derive = |name: &str, value: &String| {
+            let color = match csscolorparser::parse(value) {
+                Ok(color) => color,
+                Err(_) => return value.clone(),
+            };
+            let lighter = luminance(&color) < luminance(&background);
+            let color = match name {
+                "--bg-0" => mirror(&color, self.scheme()),
+                name if name.starts_with("--color-") => {
+                    if contrast(&color, &derived) >= ACCENT_CONTRAST {
+                        color
+                    } else {
+                        with_contrast(&color, &derived, ACCENT_CONTRAST, lighter)
+                    }
+                }
+                _ => with_contrast(&color, &derived, contrast(&color, &background), lighter),
+            };
+            color.to_css_hex()
+        };
+
+        let colors = self
+            .colors
+            .iter()
+            .map(|variable| Variable {
+                name: variable.name.clone(),
+                value: variable
+                    .value
+                    .map_stops(|stop| derive(&variable.name, stop)),
+            })
+            .collect();
+
+        Theme {
+            prefered_color_scheme: self.prefered_color_scheme,
+            colors,
+            style: self.style.clone(),
+        }
+    }
+
+    // Checks that every card variable is defined and that text stays readable
+    // on the card background.
+    pub fn validate(&self) -> Result<()> {
+        let missing = VARIABLES
+            .iter()
+            .filter(|name| self.color(name).is_none())
+            .copied()
+            .collect::<Vec<&str>>();
+        if !missing.is_empty() {
+            return Err(
+                Error::new_invalid_theme().with(format!("missing colors {}", missing.join(", ")))
+            );
+        }
+
+        for (foreground, background, minimum) in CONTRAST {
+            let ratio = self.contrast(foreground, background).unwrap_or(0.0);
+            if ratio < minimum {
+                return Err(Error::new_invalid_theme().with(format!(
+                    "{foreground} on {background} has a contrast of {ratio:.2}, at least {minimum} is required"
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Theme {
+        get(DEFAULT).map(ThemeInfo::theme).unwrap()
+    }
+}
+
+impl From<&str> for Theme {
+    fn from(s: &str) -> Theme {
+        get(s).map(ThemeInfo::theme).unwrap_or_default()
+    }
+}
+
+impl From<String> for Theme {
+    fn from(s: String) -> Theme {
+        s.as_str().into()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorScheme {
+    Light,
+    Dark,
+}
+
+impl TryFrom<&str> for ColorScheme {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<ColorScheme> {
+        match value.to_ascii_lowercase().as_str() {
+            "light" => Ok(ColorScheme::Light),
+            "dark" => Ok(ColorScheme::Dark),
+            _ => Err(Error::new_invalid_parameter()
+                .with(format!("scheme must be light or dark, got \"{value}\""))),
+        }
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct Variable {
-    name: &'static str,
-    value: &'static str,
+    name: String,
+    value: Paint,
+}
+const VARIABLES: [&str; 12] = [
+    "--bg-0",
+    "--bg-1",
+    "--bg-2",
+    "--bg-3",
+    "--text-0",
+    "--text-1",
+    "--text-2",
+    "--text-3",
+    "--color-0",
+    "--color-1",
+    "--color-2",
+    "--color-3",
+];
+
+// Text colors checked against the background, with the minimum WCAG AA
+// ratio: secondary texts (ranking, counts) are only held to the large text
+// threshold.
+const CONTRAST: [(&str, &str, f64); 4] = [
+    ("--text-0", "--bg-0", 4.5),
+    ("--text-1", "--bg-0", 3.0),
+    ("--text-2", "--bg-0", 3.0),
+    ("--text-3", "--bg-0", 3.0),
+];
+
+// Luminance with the same contrast against black and white.
+const MID_GRAY: f64 = 0.179;
+// Light backgrounds sit much closer to white than dark ones to black, their
+// distance to the extreme is scaled by this factor when mirrored.
+const BACKGROUND_SPREAD: f32 = 4.0;
+// WCAG minimum for graphical objects such as the progress rings.
+const ACCENT_CONTRAST: f64 = 3.0;
+
+// Relative luminance as defined by WCAG 2, transparency is ignored.
+fn luminance(color: &Color) -> f64 {
+    let channel = |c: f32| {
+        let c = c as f64;
+        if c <= 0.04045 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+
+    0.2126 * channel(color.r) + 0.7152 * channel(color.g) + 0.0722 * channel(color.b)
+}
+
+fn contrast(a: &Color, b: &Color) -> f64 {
+    let (a, b) = (luminance(a), luminance(b));
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+// Mirrors the OKLab lightness of a background of the given scheme.
+fn mirror(background: &Color, scheme: ColorScheme) -> Color {
+    let [l, c, h, alpha] = background.to_oklcha();
+    let l = match scheme {
+        ColorScheme::Light => (1.0 - l) * BACKGROUND_SPREAD,
+        ColorScheme::Dark => 1.0 - l / BACKGROUND_SPREAD,
+    };
+    Color::from_oklcha(l.clamp(0.0, 1.0), c, h, alpha).clamp()
+}
+
+// Moves the OKLab lightness of `color` away from `background`, on the
+// `lighter` or darker side, until the contrast reaches `ratio`. The extreme is
+// returned when the ratio cannot be reached.
+fn with_contrast(color: &Color, background: &Color, ratio: f64, lighter: bool) -> Color {
+    let [_, c, h, alpha] = color.to_oklcha();
+    let start = background.to_oklcha()[0];
+    // Colors are compared once rounded to 8 bit channels, as they are written.
+    let at = |l: f32| {
+        let [r, g, b, a] = Color::from_oklcha(l, c, h, alpha).clamp().to_rgba8();
+        Color::from_rgba8(r, g, b, a)
+    };
+    let (mut near, mut far) = if lighter { (start, 1.0) } else { (start, 0.0) };
+
+    if contrast(&at(far), background) < ratio {
+        return at(far);
+    }
+    for _ in 0..24 {
+        let middle = (near + far) / 2.0;
+        if contrast(&at(middle), background) < ratio {
+            near = middle;
+        } else {
+            far = middle;
+        }
+    }
+
+    at(far)
+}
+
+// Whether `name`, with or without its leading `--`, is a themable color.
+pub fn is_variable(name: &str) -> bool {
+    VARIABLES.contains(&format!("--{}", name.trim_start_matches("--")).as_str())
 }
 
 impl Variable {
-    const fn new(name: &'static str, value: &'static str) -> Variable {
-        Variable { name, value }
-    }
-}
-
-pub const LIGHT: Theme = Theme::new(
-    "light",
-    macros::variables! {
-        "--bg-0": "#fff",
-        "--bg-1": "#e5e5e5",
-        "--bg-2": "#e5e5e5",
-        "--bg-3": "#e5e5e5",
-        "--text-0": "#000",
-        "--text-1": "#808080",
-        "--text-2": "#808080",
-        "--text-3": "#808080"
-    },
-    "",
-);
-
-pub const DARK: Theme = Theme::new(
-    "dark",
-    macros::variables! {
-        "--bg-0": "#101010",
-        "--bg-1": "#404040",
-        "--bg-2": "#404040",
-        "--bg-3": "#404040",
-        "--text-0": "#f0f0f0",
-        "--text-1": "#dcdcdc",
-        "--text-2": "#dcdcdc",
-        "--text-3": "#dcdcdc",
-        "--color-0": "#ffa116",
-        "--color-1": "#5cb85c",
-        "--color-2": "#f0ad4e",
-        "--color-3": "#d9534f"
-    },
-    "#L { fill: #fff }",
-);
-
-pub const FERRARI: Theme = Theme::new(
-    "ferrari",
-    macros::variables! {
-        "--bg-0": "#a6051a",
-        "--bg-1": "#ed1c24",
-        "--bg-2": "#ed1c24",
-        "--bg-3": "#ed1c24",
-        "--text-0": "#fff200",
-        "--text-1": "#ffffff",
-        "--text-2": "#ffffff",
-        "--text-3": "#ffffff",
-        "--color-0": "#fff200",
-        "--color-1": "#009a4e",
-        "--color-2": "#ffffff",
-        "--color-3": "#111111"
-    },
-    "#L { fill: #ffffff }",
-);
-
-macros::impl_themes! {
-    "light" => LIGHT;
-    "dark" => DARK;
-    "ferrari" => FERRARI;
-}
-
-mod macros {
-    macro_rules! variables {
-        {$($name:literal: $value:literal),*} => {
-            &[$(Variable::new($name, $value)),*]
-        };
+    // The leading `--` is optional in `name`.
+    pub fn parse(name: &str, value: &str) -> Result<Variable> {
+        let name = format!("--{}", name.trim_start_matches("--"));
+        if !is_variable(&name) {
+            return Err(
+                Error::new_invalid_parameter().with(format!("\"{name}\" is not a theme color"))
+            );
+        }
+
+        let value = Paint::parse(value).map_err(|_| {
+            Error::new_invalid_parameter().with(format!(
+                "{name} must be a CSS color or gradient, got \"{}\"",
+                value.trim()
+            ))
+        })?;
+
+        Ok(Variable { name, value })
     }
 
-    macro_rules! impl_themes {
-        {$($name:literal => $theme:ident);*;} => {
-            impl From<&str> for Theme {
-                fn from(s: &str) -> Theme {
-                    match s {
-                        $($name => $theme,)*
-                        _ => LIGHT,
-                    }
-                }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn value(&self) -> String {
+        self.value.to_string()
+    }
+
+    pub fn paint(&self) -> &Paint {
+        &self.value
+    }
+}
+
+// A compact palette is a comma separated list of `name:paint` pairs, e.g.
+// `bg-0:1a1b26,text-0:c0caf5,color-0:linear-gradient(ff9e64,e0af68)`.
+pub fn parse_palette(palette: &str) -> Result<Vec<Variable>> {
+    paint::split_arguments(palette)
+        .into_iter()
+        .filter(|entry| !entry.trim().is_empty())
+        .map(|entry| match entry.split_once(':') {
+            Some((name, value)) => Variable::parse(name.trim(), value),
+            None => Err(Error::new_invalid_parameter().with(format!(
+                "palette entries must be name:paint, got \"{entry}\""
+            ))),
+        })
+        .collect()
+}
+
+// Themes are contributed through `themes.toml`, which is embedded in the
+// binary and parsed on first use.
+const CATALOG: &str = include_str!("../themes.toml");
+const DEFAULT: &str = "light";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ThemeInfo {
+    pub name: String,
+    pub author: Option<String>,
+    pub description: Option<String>,
+    pub scheme: ColorScheme,
+    // Theme used for the opposite `prefers-color-scheme`.
+    pub pair: Option<String>,
+    pub colors: Vec<Variable>,
+    pub style: String,
+}
+
+impl ThemeInfo {
+    pub fn theme(&self) -> Theme {
+        Theme {
+            prefered_color_scheme: "no-preference",
+            colors: self.colors.clone(),
+            style: self.style.clone(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CatalogEntry {
+    author: Option<String>,
+    description: Option<String>,
+    scheme: ColorScheme,
+    pair: Option<String>,
+    #[serde(default)]
+    style: String,
+    colors: BTreeMap<String, String>,
+}
+
+pub fn catalog() -> &'static [ThemeInfo] {
+    static THEMES: OnceLock<Vec<ThemeInfo>> = OnceLock::new();
+    THEMES.get_or_init(|| parse_catalog(CATALOG).expect("themes.toml is validated by the tests"))
+}
+
+pub fn get(name: &str) -> Option<&'static ThemeInfo> {
+    catalog().iter().find(|theme| theme.name == name)
+}
+
+// Returns the `(light, dark)` themes to use for `name`.
+// Themes without a pair get a derived one.
+pub fn pair(name: &str) -> Option<(Theme, Theme)> {
+    let info = get(name)?;
+    let other = match &info.pair {
+        Some(pair) => get(pair)?.theme(),
+        None => info.theme().counterpart(),
+    };
+    match info.scheme {
+        ColorScheme::Light => Some((info.theme(), other)),
+        ColorScheme::Dark => Some((other, info.theme())),
+    }
+}
+
+pub fn parse_catalog(catalog: &str) -> Result<Vec<ThemeInfo>> {
+    let entries: BTreeMap<String, CatalogEntry> =
+        toml::from_str(catalog).map_err(|e| Error::new_invalid_theme().with(e))?;
+
+    let themes = entries
+        .into_iter()
+        .map(|(name, entry)| {
+            let mut colors = entry
+                .colors
+                .iter()
+                .map(|(key, value)| Variable::parse(key, value))
+                .collect::<Result<Vec<Variable>>>()
+                .map_err(|e| Error::new_invalid_theme().with(format!("{name}: {}", e.hint())))?;
+            // Keep the declaration order of the cards regardless of the file.
+            colors.sort_by_key(|c| VARIABLES.iter().position(|v| *v == c.name));
+
+            if entry.style.contains('<') {
+                return Err(
+                    Error::new_invalid_theme().with(format!("{name}: style cannot contain markup"))
+                );
             }
 
-            impl From<String> for Theme {
-                fn from(s: String) -> Theme {
-                    s.as_str().into()
+            let info = ThemeInfo {
+                name,
+                author: entry.author,
+                description: entry.description,
+                scheme: entry.scheme,
+                pair: entry.pair,
+                colors,
+                style: entry.style,
+            };
+            info.theme().validate().map_err(|e| {
+                let cause = e.source().map(|c| c.to_string()).unwrap_or_default();
+                Error::new_invalid_theme().with(format!("{}: {cause}", info.name))
+            })?;
+
+            Ok(info)
+        })
+        .collect::<Result<Vec<ThemeInfo>>>()?;
+
+    for theme in &themes {
+        if let Some(pair) = &theme.pair {
+            match themes.iter().find(|t| &t.name == pair) {
+                Some(other) if other.scheme != theme.scheme => {}
+                Some(_) => {
+                    return Err(Error::new_invalid_theme().with(format!(
+                        "{}: paired theme {pair} has the same color scheme",
+                        theme.name
+                    )))
+                }
+                None => {
+                    return Err(Error::new_invalid_theme()
+                        .with(format!("{}: unknown paired theme {pair}", theme.name)))
                 }
             }
-        };
+        }
+    }
+    if !themes.iter().any(|t| t.name == DEFAULT) {
+        return Err(Error::new_invalid_theme().with(format!("{DEFAULT} theme is missing")));
+    }
+
+    Ok(themes)
 }
 
-    pub(super) use impl_themes;
-    pub(super) use variables;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_color_schemes_are_rejected() {
+        assert_eq!(ColorScheme::try_from("Dark").unwrap(), ColorScheme::Dark);
+        let error = ColorScheme::try_from("drak").unwrap_err();
+        assert_eq!(error.hint(), "scheme must be light or dark, got \"drak\"");
+    }
+
+    #[test]
+    fn overrides_are_merged_onto_the_base_theme() {
+        let mut theme = Theme::from("dark");
+        theme.set_color("bg-0", "1a1b26").unwrap();
+        theme.set_color("--color-1", "rebeccapurple").unwrap();
+
+        let vars = theme.format_vars().concat();
+        assert!(vars.starts_with("--bg-0:#1a1b26;--bg-1:#404040;"));
+        assert!(vars.contains("--color-1:#663399;"));
+        // The compiled-in theme is left untouched.
+        assert_eq!(Theme::from("dark").colors[0].value(), "#101010");
+    }
+
+    #[test]
+    fn missing_colors_are_added() {
+        let mut theme = Theme::from("light");
+        theme.set_color("color-3", "rgb(200, 0, 0)").unwrap();
+
+        assert_eq!(theme.colors.last().unwrap().value(), "#c80000");
+    }
+
+    #[test]
+    fn space_separated_colors_survive_minimized_css() {
+        let mut theme = Theme::from("light");
+        theme.set_color("bg-0", "hsl(120 50% 50%)").unwrap();
+        theme.set_color("text-0", "rgb(0 0 0)").unwrap();
+
+        let css = crate::minimize_css(&theme.format_vars().concat());
+        assert!(css.contains("--bg-0:#40bf40;"));
+        assert!(css.contains("--text-0:#000000;"));
+    }
+
+    #[test]
+    fn invalid_colors_are_rejected() {
+        assert!(Variable::parse("bg-0", "not-a-color").is_err());
+        assert!(Variable::parse("bg-0", "red;}</style><script>").is_err());
+        assert!(Variable::parse("--font-size", "#fff").is_err());
+    }
+
+    #[test]
+    fn palettes_are_parsed() {
+        let palette = parse_palette(
+            "bg-0:1a1b26, text-0:#C0CAF5,color-0:orange,color-1:radial-gradient(rgb(0, 0, 0),fff)",
+        )
+        .unwrap();
+
+        assert_eq!(
+            palette
+                .iter()
+                .map(|v| format!("{}:{}", v.name(), v.value()))
+                .collect::<Vec<_>>(),
+            [
+                "--bg-0:#1a1b26",
+                "--text-0:#c0caf5",
+                "--color-0:#ffa500",
+                "--color-1:radial-gradient(#000000, #fff)"
+            ]
+        );
+        assert!(parse_palette("bg-0").is_err());
+    }
+
+    #[test]
+    fn embedded_catalog_is_valid() {
+        let themes = parse_catalog(CATALOG).unwrap();
+
+        assert!(themes.iter().all(|theme| !theme.colors.is_empty()));
+        let (light, dark) = pair("light").unwrap();
+        assert_eq!(light.colors[0].value(), "#fff");
+        assert_eq!(dark.colors[0].value(), "#101010");
+    }
+
+    fn complete_colors() -> String {
+        Theme::from("light")
+            .colors
+            .iter()
+            .map(|c| format!("{} = \"{}\"", c.name.trim_start_matches("--"), c.value))
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+
+    #[test]
+    fn invalid_catalogs_are_rejected() {
+        let colors = complete_colors();
+        let unknown_pair =
+            format!("[light]\nscheme = \"light\"\npair = \"night\"\ncolors = {{ {colors} }}");
+        let bad_color = format!(
+            "[light]\nscheme = \"light\"\ncolors = {{ {} }}",
+            colors.replace("#ffa116", "nope")
+        );
+        let missing_default = format!("[ocean]\nscheme = \"dark\"\ncolors = {{ {colors} }}");
+        let incomplete = "[light]\nscheme = \"light\"\ncolors = { bg-0 = \"#fff\" }".to_string();
+        let unreadable = format!(
+            "[light]\nscheme = \"light\"\ncolors = {{ {} }}",
+            colors.replace("text-0 = \"#000\"", "text-0 = \"#eee\"")
+        );
+        let unknown_scheme = format!("[light]\nscheme = \"dim\"\ncolors = {{ {colors} }}");
+
+        for catalog in [
+            unknown_pair,
+            bad_color,
+            missing_default,
+            incomplete,
+            unreadable,
+            unknown_scheme,
+        ] {
+            let error = parse_catalog(&catalog).unwrap_err();
+            assert!(matches!(
+                error.kind(),
+                crate::error::ErrorKind::InvalidTheme
+            ));
+        }
+        assert!(parse_catalog(&format!(
+            "[light]\nscheme = \"light\"\ncolors = {{ {colors} }}"
+        ))
+        .is_ok());
+    }
+
+    #[test]
+    fn registered_themes_are_complete_and_readable() {
+        for info in catalog() {
+            if let Err(e) = info.theme().validate() {
+                panic!("{}: {e:?}", info.name);
+            }
+        }
+    }
+
+    #[test]
+    fn contrast_follows_wcag() {
+        let mut theme = Theme::from("light");
+        theme.set_color("text-0", "#000").unwrap();
+        theme.set_color("bg-0", "#fff").unwrap();
+        assert_eq!(theme.contrast("--text-0", "--bg-0"), Some(21.0));
+        assert_eq!(theme.contrast("--bg-0", "--bg-0"), Some(1.0));
+
+        theme.set_color("text-0", "#777").unwrap();
+        let ratio = theme.contrast("--text-0", "--bg-0").unwrap();
+        assert!((ratio - 4.48).abs() < 0.01);
+        assert!(theme.validate().is_err());
+    }
+
+    #[test]
+    fn incomplete_themes_are_rejected() {
+        let theme = Theme {
+            prefered_color_scheme: "no-preference",
+            colors: Vec::new(),
+            style: String::new(),
+        };
+
+        assert!(theme.format_vars().is_empty());
+        assert!(theme.validate().is_err());
+    }
+
+    #[test]
+    fn derived_counterparts_are_valid() {
+        for info in catalog() {
+            let theme = info.theme();
+            let derived = theme.counterpart();
+
+            assert_ne!(derived.scheme(), theme.scheme(), "{}", info.name);
+            derived.validate().unwrap();
+        }
+    }
+
+    #[test]
+    fn counterpart_keeps_hues_and_contrast() {
+        let light = Theme::from("light");
+        let dark = light.counterpart();
+
+        assert_eq!(dark.color("--bg-0"), Some("#000000"));
+        assert_eq!(dark.color("--text-0"), Some("#ffffff"));
+        // Accents that stay visible on the new background are kept.
+        assert_eq!(dark.color("--color-0"), light.color("--color-0"));
+
+        let ratio = |theme: &Theme| theme.contrast("--text-1", "--bg-0").unwrap();
+        assert!(ratio(&dark) >= ratio(&light));
+        assert!(ratio(&dark) - ratio(&light) < 0.1);
+
+        let back = dark.counterpart();
+        assert_eq!(back.scheme(), ColorScheme::Light);
+        assert_eq!(back.color("--bg-0"), Some("#ffffff"));
+    }
+
+    #[test]
+    fn unpaired_themes_get_a_derived_pair() {
+        let (light, dark) = pair("ferrari").unwrap();
+
+        assert_eq!(light.scheme(), ColorScheme::Light);
+        assert_eq!(dark.color("--bg-0"), Some("#a6051a"));
+    }
+
+    #[test]
+    fn unknown_names_fall_back_to_the_default_theme() {
+        assert_eq!(
+            Theme::from("does-not-exist").colors,
+            get(DEFAULT).unwrap().colors
+        );
+    }
 }
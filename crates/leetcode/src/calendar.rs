@@ -0,0 +1,197 @@
+use std::collections::BTreeMap;
+
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+// Weeks drawn by the heatmap, the current one included.
+pub const WEEKS: i64 = 53;
+
+// Accepted submissions per day over the past year. Days are counted from the
+// Unix epoch in UTC, like the timestamps of LeetCode's submission calendar.
+#[derive(Debug, Clone, Default)]
+pub struct Calendar {
+    days: BTreeMap<i64, u32>,
+    active_years: Vec<u32>,
+    total_active_days: u32,
+    // Day the calendar was fetched, the last one of the heatmap.
+    today: i64,
+    // Most submissions on a day drawn by the heatmap.
+    busiest: u32,
+}
+
+impl Calendar {
+    pub fn new(
+        days: BTreeMap<i64, u32>,
+        active_years: Vec<u32>,
+        total_active_days: u32,
+        today: i64,
+    ) -> Calendar {
+        let busiest = days
+            .range(first_day(today)..=today)
+            .map(|(_, count)| *count)
+            .max()
+            .unwrap_or_default();
+
+        Calendar {
+            days,
+            active_years,
+            total_active_days,
+            today,
+            busiest,
+        }
+    }
+
+    pub fn count(&self, day: i64) -> u32 {
+        self.days.get(&day).copied().unwrap_or_default()
+    }
+
+    pub fn active_years(&self) -> &[u32] {
+        &self.active_years
+    }
+
+    pub fn total_active_days(&self) -> u32 {
+        self.total_active_days
+    }
+
+    // Sunday starting the first week of the heatmap.
+    pub fn first_day(&self) -> i64 {
+        first_day(self.today)
+    }
+
+    pub fn today(&self) -> i64 {
+        self.today
+    }
+
+    // Intensity of a day from 0 to 4, relative to the busiest day drawn.
+    pub fn level(&self, day: i64) -> u32 {
+        match self.count(day) {
+            0 => 0,
+            count => (4 * count).div_ceil(self.busiest).clamp(1, 4),
+        }
+    }
+}
+
+// Seconds since the Unix epoch.
+pub fn now() -> i64 {
+    #[cfg(feature = "worker")]
+    let millis = worker::Date::now().as_millis();
+    #[cfg(not(feature = "worker"))]
+    let millis = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as u64);
+
+    (millis / 1000) as i64
+}
+
+// The current day, in UTC.
+pub fn today() -> i64 {
+    now().div_euclid(86_400)
+}
+
+// How long ago `timestamp` was at `now`, both in seconds, in the largest
+// whole unit.
+pub fn relative(timestamp: i64, now: i64) -> String {
+    let elapsed = (now - timestamp).max(0);
+    let (count, unit) = [
+        (365 * 86_400, "year"),
+        (30 * 86_400, "month"),
+        (86_400, "day"),
+        (3_600, "hour"),
+        (60, "minute"),
+    ]
+    .into_iter()
+    .map(|(seconds, unit)| (elapsed / seconds, unit))
+    .find(|(count, _)| *count > 0)
+    .unwrap_or((0, ""));
+
+    match count {
+        0 => "just now".to_string(),
+        1 => format!("1 {unit} ago"),
+        count => format!("{count} {unit}s ago"),
+    }
+}
+
+fn first_day(today: i64) -> i64 {
+    today - weekday(today) - (WEEKS - 1) * 7
+}
+
+// 0 for Sunday to 6 for Saturday, the epoch was a Thursday.
+pub fn weekday(day: i64) -> i64 {
+    (day + 4).rem_euclid(7)
+}
+
+// Year, month from 1 and day of the month from 1 of a day since the epoch.
+pub fn civil(day: i64) -> (i64, u32, u32) {
+    // Shifted to start on March 1st of year 0, so that leap days end years.
+    let day = day + 719_468;
+    let era = day.div_euclid(146_097);
+    let day_of_era = day.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day_of_month = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    (year, month, day_of_month)
+}
+
+pub fn month_name(month: u32) -> &'static str {
+    MONTHS[(month - 1) as usize]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2024-06-15, a Saturday.
+    const DAY: i64 = 19_889;
+
+    #[test]
+    fn days_convert_to_dates() {
+        assert_eq!(civil(0), (1970, 1, 1));
+        assert_eq!(civil(DAY), (2024, 6, 15));
+        assert_eq!(civil(19_782), (2024, 2, 29));
+        assert_eq!(civil(-1), (1969, 12, 31));
+        assert_eq!(weekday(0), 4);
+        assert_eq!(weekday(DAY), 6);
+    }
+
+    #[test]
+    fn elapsed_time_is_written_in_the_largest_unit() {
+        let now = DAY * 86_400;
+
+        assert_eq!(relative(now - 20, now), "just now");
+        assert_eq!(relative(now + 20, now), "just now");
+        assert_eq!(relative(now - 60, now), "1 minute ago");
+        assert_eq!(relative(now - 3 * 3_600 - 59, now), "3 hours ago");
+        assert_eq!(relative(now - 86_400, now), "1 day ago");
+        assert_eq!(relative(now - 45 * 86_400, now), "1 month ago");
+        assert_eq!(relative(now - 800 * 86_400, now), "2 years ago");
+    }
+
+    #[test]
+    fn heatmap_starts_on_a_sunday_a_year_ago() {
+        let calendar = Calendar::new(BTreeMap::new(), Vec::new(), 0, DAY);
+
+        assert_eq!(weekday(calendar.first_day()), 0);
+        assert_eq!((DAY - calendar.first_day()) / 7, WEEKS - 1);
+    }
+
+    #[test]
+    fn levels_are_relative_to_the_busiest_day() {
+        let days = BTreeMap::from([(DAY, 8), (DAY - 1, 1), (DAY - 2, 4), (DAY - 400, 100)]);
+        let calendar = Calendar::new(days, vec![2024], 3, DAY);
+
+        assert_eq!(calendar.level(DAY), 4);
+        assert_eq!(calendar.level(DAY - 1), 1);
+        assert_eq!(calendar.level(DAY - 2), 2);
+        assert_eq!(calendar.level(DAY - 3), 0);
+    }
+}
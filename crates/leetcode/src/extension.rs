@@ -1,5 +1,7 @@
 use core::{error::Result, font::Font, item::Item, theme::Theme, Extension as ExtensionTrait};
 
+use crate::font::Fonts;
+
 use crate::Generator;
 
 #[derive(Clone, Debug)]
@@ -7,7 +9,11 @@ pub enum Extension {
     Animation,
     Theme(Theme),
     Themes(Vec<Theme>),
-    Font(Font),
+    Font(Fonts),
+    // The last accepted submissions, listed below the card.
+    RecentSubmissions(usize),
+    // A row of the earned badges, at most this many of them.
+    Badges(usize),
 }
 
 impl ExtensionTrait<Generator> for Extension {
@@ -21,19 +27,24 @@ impl ExtensionTrait<Generator> for Extension {
             Extension::Animation => animation::extend(generator, items, style),
             Extension::Theme(theme) => theme.extend(generator, items, style).await,
             Extension::Themes(themes) => themes::extend(themes, generator, items, style).await,
-            Extension::Font(font) => font::extend(font, generator, items, style).await,
+            Extension::Font(fonts) => font::extend(fonts, generator, items, style).await,
+            Extension::RecentSubmissions(limit) => recent::extend(*limit, generator, items).await,
+            Extension::Badges(limit) => badges::extend(*limit, generator, items).await,
         }
     }
 }
 
 mod animation {
-    use crate::Generator;
+    use crate::{
+        layout::{Kind, Layout},
+        Generator,
+    };
     use core::{error::Result, item::Item};
 
     const KEYFRAME: &str = "@keyframes fade_in{from{opacity:0}to{opacity:1}}";
 
-    fn order() -> Vec<&'static str> {
-        vec![
+    fn order(layout: &Layout, recent: bool, badges: bool) -> Vec<&'static str> {
+        let mut order = vec![
             "#icon",
             "#username",
             "#ranking",
@@ -52,7 +63,25 @@ mod animation {
             "#hard-solved-count",
             "#hard-solved-bg",
             "#hard-solved-progress",
-        ]
+        ];
+        if layout.kind() == Kind::Wide {
+            order.extend(["#streak-stat", "#submissions-stat", "#country-stat"]);
+        }
+        match layout.kind() {
+            Kind::Contest => order.extend(["#contest", "#rating-chart"]),
+            Kind::Skills => order.extend(["#languages", "#tags"]),
+            _ => {}
+        }
+        if layout.has_heatmap() {
+            order.push("#heatmap");
+        }
+        if recent {
+            order.push("#recent");
+        }
+        if badges {
+            order.push("#badges");
+        }
+        order
     }
 
     fn circle(selector: &str, len: f64, delay: f32) -> String {
@@ -66,22 +95,28 @@ mod animation {
         _: &mut Vec<Item>,
         style: &mut Vec<String>,
     ) -> Result<()> {
+        let layout = generator.config.layout()?;
         let mut css = KEYFRAME.to_string();
         let speed = 1_f32;
 
-        order().iter().enumerate().for_each(|(i, select)| {
-            css.push_str(&format!(
-                "{}{{opacity:0;animation:fade_in {}s ease {}s 1 forwards}}",
-                select,
-                0.3 / speed,
-                0.1 * i as f32
-            ))
-        });
+        let recent = layout.kind() != Kind::Compact && generator.recent.is_some();
+        order(&layout, recent, generator.badges)
+            .iter()
+            .enumerate()
+            .for_each(|(i, select)| {
+                css.push_str(&format!(
+                    "{}{{opacity:0;animation:fade_in {}s ease {}s 1 forwards}}",
+                    select,
+                    0.3 / speed,
+                    0.1 * i as f32
+                ))
+            });
 
         let (solved, total) = generator.get_user_info().problems_stats();
+        let r = layout.ring_radius();
         css.push_str(&circle(
             "#total-solved-ring",
-            std::f64::consts::PI * 80.0 * solved as f64 / total as f64,
+            2.0 * r * std::f64::consts::PI * solved as f64 / total as f64,
             0.7,
         ));
 
@@ -108,27 +143,119 @@ mod themes {
     }
 }
 
-mod font {
-    use super::Font;
+mod recent {
+    use crate::{calendar, font, graphql, item, layout::Kind, Generator, RecentSubmissions};
+    use core::{
+        error::Result,
+        item::{FontStyle, Item},
+    };
+
+    pub async fn extend(
+        limit: usize,
+        generator: &mut Generator,
+        body: &mut Vec<Item>,
+    ) -> Result<()> {
+        let layout = generator.config.layout()?;
+        // A badge has no room for a list.
+        if layout.kind() == Kind::Compact {
+            return Ok(());
+        }
+
+        if generator.recent.is_none() {
+            let user_id = graphql::Id::new(&generator.config.username);
+            let submissions = graphql::Client::new(user_id)
+                .set_verbose(generator.verbose)
+                .recent_ac_submissions(limit)
+                .await?;
+            generator.recent = Some(RecentSubmissions {
+                submissions,
+                fetched_at: calendar::now(),
+            });
+        }
+
+        let metrics = generator.metrics(font::BODY, FontStyle::default());
+        let recent = generator.recent.as_ref().expect("fetched above");
+        let submissions = &recent.submissions[..recent.submissions.len().min(limit)];
+        let y = layout.with_appended(generator.appended).height() as f64;
+        body.push(item::recent_submissions(
+            &layout,
+            y,
+            submissions,
+            recent.fetched_at,
+            &metrics,
+        ));
+        generator.appended += layout.list_height(submissions.len());
+
+        Ok(())
+    }
+}
+
+mod badges {
+    use crate::{icon, item, layout::Kind, log, Generator};
+    use core::{error::Result, item::Item};
+    use futures_util::future::join_all;
+
+    pub async fn extend(
+        limit: usize,
+        generator: &mut Generator,
+        body: &mut Vec<Item>,
+    ) -> Result<()> {
+        let layout = generator.config.layout()?;
+        // A badge has no room for a row of them.
+        if layout.kind() == Kind::Compact {
+            return Ok(());
+        }
+
+        let badges = generator.get_user_info().badges.clone();
+        let shown = &badges[..badges.len().min(limit).min(layout.badges_per_row())];
+        let icons = join_all(shown.iter().map(|badge| icon::load(&badge.icon)))
+            .await
+            .into_iter()
+            .zip(shown)
+            .map(|(icon, badge)| {
+                // A missing icon should not cost the whole card.
+                icon.map_err(|e| {
+                    log! {generator.verbose => "skipping icon of badge {:?}: {:?}", badge.name, e};
+                })
+                .ok()
+            })
+            .collect::<Vec<_>>();
+
+        let y = layout.with_appended(generator.appended).height() as f64;
+        let row = shown
+            .iter()
+            .zip(&icons)
+            .map(|(badge, icon)| (badge, icon.as_deref()))
+            .collect::<Vec<_>>();
+        body.push(item::badges(&layout, y, &row, badges.len()));
+        generator.badges = true;
+        generator.appended += layout.badges_height();
+
+        Ok(())
+    }
+}
+
+pub(crate) mod font {
+    use super::Fonts;
     use crate::Generator;
     use core::{error::Result, item::Item};
 
     pub async fn extend(
-        font: &Font,
-        _: &mut Generator,
+        fonts: &Fonts,
+        generator: &mut Generator,
         _: &mut Vec<Item>,
         style: &mut Vec<String>,
     ) -> Result<()> {
-        let font = font.fetch().await?;
-        style.push(format!(
-            r##"@font-face{{font-family:"{}";src:url("{}") format("woff2")}}"##,
-            font.name, font.base64
-        ));
-        let font_family = match font.name.as_str() {
-            "sans" | "serif" | "monospace" => font.name.clone(),
-            _ => format!(r#""{}""#, font.name),
-        };
-        style.push(format!("*{{font-family:{}}}", font_family));
+        for font in fonts.fonts() {
+            let json = font.load().await?;
+            generator.fonts.extend(json.ttf_bytes());
+            // The `@font-face` rules are written once the card is built, with
+            // only the faces and glyphs it uses.
+            generator
+                .font_faces
+                .push((json, fonts.families_drawn_by(font)));
+        }
+        style.push(fonts.css());
         Ok(())
     }
 }
@@ -145,14 +272,20 @@ impl From<Vec<Theme>> for Extension {
     }
 }
 
+impl From<Fonts> for Extension {
+    fn from(fonts: Fonts) -> Self {
+        Extension::Font(fonts)
+    }
+}
+
 impl From<Font> for Extension {
     fn from(font: Font) -> Self {
-        Extension::Font(font)
+        Extension::Font(font.into())
     }
 }
 
 impl From<&Font> for Extension {
     fn from(font: &Font) -> Self {
-        Extension::Font(*font)
+        Extension::Font((*font).into())
     }
 }
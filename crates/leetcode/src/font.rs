@@ -1,4 +1,8 @@
-use core::{error::Result, Extension};
+use core::{
+    error::Result,
+    font::{Font, FontStack},
+    Extension,
+};
 
 macro_rules! fonts {
     {$($const:ident),*} => {
@@ -10,23 +14,93 @@ macro_rules! fonts {
 
 fonts! {BALOO_2, FORMULA_1}
 
-impl Extension<super::Generator> for core::font::Font {
+// Families set on the headings and numbers of the card, the rest of the text
+// is drawn with the body font.
+pub const HEADING: &str = "var(--font-heading)";
+pub const NUMBER: &str = "var(--font-number)";
+// Family of the rest of the text.
+pub const BODY: &str = "var(--font-body)";
+
+// Font stacks of the card, headings and numbers are drawn with the body stack
+// unless they have their own.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Fonts {
+    pub body: FontStack,
+    pub heading: Option<FontStack>,
+    pub number: Option<FontStack>,
+}
+
+impl Fonts {
+    fn stacks(&self) -> [(Option<&'static str>, &FontStack); 3] {
+        [
+            (None, &self.body),
+            (Some(HEADING), self.heading.as_ref().unwrap_or(&self.body)),
+            (Some(NUMBER), self.number.as_ref().unwrap_or(&self.body)),
+        ]
+    }
+
+    // Stack drawing the text with the `font-family` value `family`.
+    pub fn stack(&self, family: Option<&str>) -> &FontStack {
+        self.stacks()
+            .into_iter()
+            .find(|(f, _)| *f == family)
+            .map_or(&self.body, |(_, stack)| stack)
+    }
+
+    // Fonts embedded in the card, each one once.
+    pub fn fonts(&self) -> Vec<Font> {
+        let mut fonts = Vec::new();
+        for font in self.stacks().iter().flat_map(|(_, stack)| stack.fonts()) {
+            if !fonts.contains(&font) {
+                fonts.push(font);
+            }
+        }
+        fonts
+    }
+
+    // `font-family` values of the text that `font` may draw.
+    pub fn families_drawn_by(&self, font: Font) -> Vec<Option<String>> {
+        self.stacks()
+            .iter()
+            .filter(|(_, stack)| stack.fonts().any(|f| f == font))
+            .map(|(family, _)| family.map(str::to_string))
+            .collect()
+    }
+
+    pub fn css(&self) -> String {
+        let [(_, body), (_, heading), (_, number)] = self.stacks();
+        format!(
+            ":root{{--font-body:{};--font-heading:{};--font-number:{}}}*{{font-family:var(--font-body)}}",
+            body.css(),
+            heading.css(),
+            number.css()
+        )
+    }
+}
+
+impl From<FontStack> for Fonts {
+    fn from(body: FontStack) -> Self {
+        Fonts {
+            body,
+            heading: None,
+            number: None,
+        }
+    }
+}
+
+impl From<Font> for Fonts {
+    fn from(font: Font) -> Self {
+        FontStack::from(font).into()
+    }
+}
+
+impl Extension<super::Generator> for Fonts {
     async fn extend(
         &self,
-        _: &mut super::Generator,
-        _: &mut Vec<core::item::Item>,
+        generator: &mut super::Generator,
+        body: &mut Vec<core::item::Item>,
         style: &mut Vec<String>,
     ) -> Result<()> {
-        let font = self.fetch().await?;
-        style.push(format!(
-            r##"@font-face{{font-family:"{}";src:url("{}") format("woff2")}}"##,
-            font.name, font.base64
-        ));
-        let font_family = match font.name.as_str() {
-            "sans" | "serif" | "monospace" => font.name.clone(),
-            _ => format!(r#""{}""#, font.name),
-        };
-        style.push(format!("*{{font-family:{}}}", font_family));
-        Ok(())
+        crate::extension::font::extend(self, generator, body, style).await
     }
 }
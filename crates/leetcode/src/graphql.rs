@@ -1,10 +1,12 @@
+use std::collections::BTreeMap;
+
 use core::error::Result;
 use serde::Serialize;
 
-use super::UserInfo;
+use super::{calendar::Calendar, Difficulty, RecentSubmission, UserInfo};
 
 const QUERY: &str = r#"
-query UserInfo($id: String!) {
+query UserInfo($id: String!, $calendar: Boolean!, $contest: Boolean!, $skills: Boolean!, $badges: Boolean!) {
     problems: allQuestionsCount {
         difficulty
         count
@@ -26,12 +28,68 @@ query UserInfo($id: String!) {
                 submissions
             }
         }
+        languageProblemCount @include(if: $skills) {
+            languageName
+            problemsSolved
+        }
+        tagProblemCounts @include(if: $skills) {
+            advanced {
+                tagName
+                problemsSolved
+            }
+            intermediate {
+                tagName
+                problemsSolved
+            }
+            fundamental {
+                tagName
+                problemsSolved
+            }
+        }
+        badges @include(if: $badges) {
+            name: displayName
+            icon
+            creationDate
+        }
+        activeBadge @include(if: $badges) {
+            name: displayName
+            icon
+            creationDate
+        }
         userCalendar {
             streak
+            activeYears @include(if: $calendar)
+            totalActiveDays @include(if: $calendar)
+            submissionCalendar @include(if: $calendar)
+        }
+    }
+    contest: userContestRanking(username: $id) @include(if: $contest) {
+        attendedContestsCount
+        rating
+        globalRanking
+        totalParticipants
+        topPercentage
+    }
+    contestHistory: userContestRankingHistory(username: $id) @include(if: $contest) {
+        attended
+        rating
+        ranking
+        contest {
+            title
+            startTime
         }
     }
 }"#;
 
+const RECENT_AC_QUERY: &str = r#"
+query RecentAcSubmissions($id: String!, $limit: Int!) {
+    recentAcSubmissionList(username: $id, limit: $limit) {
+        title
+        titleSlug
+        timestamp
+    }
+}"#;
+
 #[derive(Debug)]
 pub struct Client<'a> {
     client: reqwest::Client,
@@ -78,15 +136,41 @@ impl<'a> Client<'a> {
         Ok(())
     }
 
-    pub async fn get(mut self) -> Result<UserInfo> {
+    // Only the given sections are asked for, the others are left empty.
+    pub async fn get(mut self, sections: Sections) -> Result<UserInfo> {
+        let variables = UserVariables {
+            id: self.user_id.id,
+            sections,
+        };
+        let bytes = self.post(QUERY, variables).await?;
+        parse_response(&bytes)
+    }
+
+    // Last accepted submissions, newest first. The list does not tell the
+    // difficulty of the problems, they are asked for in a second query.
+    pub async fn recent_ac_submissions(mut self, limit: usize) -> Result<Vec<RecentSubmission>> {
+        let variables = RecentVariables {
+            id: self.user_id.id,
+            limit,
+        };
+        let bytes = self.post(RECENT_AC_QUERY, variables).await?;
+        let submissions = parse_recent_ac(&bytes)?;
+        if submissions.is_empty() {
+            return Ok(submissions);
+        }
+
+        let bytes = self
+            .post(&difficulties_query(&submissions), serde_json::json!({}))
+            .await?;
+        with_difficulties(submissions, &bytes)
+    }
+
+    async fn post(&mut self, query: &str, variables: impl Serialize) -> Result<Vec<u8>> {
         if self.csrf.is_none() {
             self.init().await?;
         }
 
-        let body = RequestBody {
-            query: QUERY,
-            variables: self.user_id,
-        };
+        let body = RequestBody { query, variables };
 
         let req = self
             .client
@@ -108,35 +192,123 @@ impl<'a> Client<'a> {
             .build()?;
 
         crate::log! { self.verbose => "requesting and reading bytes..." };
-        let bytes = self.client.execute(req).await?.bytes().await?;
+        let bytes = self
+            .client
+            .execute(req)
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?
+            .to_vec();
         crate::log! { self.verbose => "bytes read" };
 
-        match serde_json::from_slice::<GraphQLResponse>(&bytes) {
-            Ok(resp) => resp.try_into(),
-            Err(e) => Err(e.into()),
-        }
+        Ok(bytes)
+    }
+}
+
+fn parse_response(bytes: &[u8]) -> Result<UserInfo> {
+    serde_json::from_slice::<GraphQLResponse>(bytes)?.try_into()
+}
+
+fn parse_recent_ac(bytes: &[u8]) -> Result<Vec<RecentSubmission>> {
+    let response = serde_json::from_slice::<RecentAcResponse>(bytes)?;
+    // `null` when no user matches the requested username.
+    let list = response
+        .data
+        .recent_ac_submission_list
+        .ok_or_else(core::error::Error::new_user_not_found)?;
+
+    list.into_iter()
+        .map(|submission| {
+            Ok(RecentSubmission {
+                title: submission.title,
+                title_slug: submission.title_slug,
+                timestamp: submission.timestamp.parse().map_err(|e| {
+                    core::error::Error::new(core::error::ErrorKind::SerdeJson).with(e)
+                })?,
+                difficulty: None,
+            })
+        })
+        .collect()
+}
+
+// One aliased `question` field per submission, the slugs are written as JSON
+// strings which are valid GraphQL strings.
+fn difficulties_query(submissions: &[RecentSubmission]) -> String {
+    let fields = submissions
+        .iter()
+        .enumerate()
+        .map(|(i, submission)| {
+            format!(
+                "q{i}: question(titleSlug: {}) {{ difficulty }}",
+                serde_json::Value::from(submission.title_slug.as_str())
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    format!("query Difficulties {{\n{fields}\n}}")
+}
+
+fn with_difficulties(
+    mut submissions: Vec<RecentSubmission>,
+    bytes: &[u8],
+) -> Result<Vec<RecentSubmission>> {
+    let response = serde_json::from_slice::<DifficultiesResponse>(bytes)?;
+
+    for (i, submission) in submissions.iter_mut().enumerate() {
+        submission.difficulty = response
+            .data
+            .get(&format!("q{i}"))
+            .and_then(|question| question.as_ref())
+            .and_then(|question| Difficulty::try_from(question.difficulty.as_str()).ok());
     }
+    Ok(submissions)
 }
 
 fn parse_cookie(header: &str) -> Result<(&str, Option<&str>)> {
     let mut parts = header.split(';');
 
-    let cookie = parts.next().ok_or(())?.split('=').last().ok_or(())?;
+    let cookie = parts.next().ok_or(())?.split('=').next_back().ok_or(())?;
 
     Ok((cookie, None))
 }
 
-#[derive(serde::Serialize, Debug)]
+#[derive(serde::Serialize, Debug, Clone, Copy)]
 pub struct Id<'a> {
     id: &'a str,
 }
 
 impl<'a> Id<'a> {
-    pub fn new(id: &'a str) -> Id {
+    pub fn new(id: &'a str) -> Id<'a> {
         Id { id }
     }
 }
 
+// Parts of the profile that only some cards draw, fetched on demand.
+#[derive(serde::Serialize, Debug, Default, Clone, Copy, PartialEq)]
+pub struct Sections {
+    // The submission counts of every day, the streak is always fetched.
+    pub calendar: bool,
+    pub contest: bool,
+    // The solved problems per language and per topic.
+    pub skills: bool,
+    pub badges: bool,
+}
+
+#[derive(serde::Serialize)]
+struct UserVariables<'a> {
+    id: &'a str,
+    #[serde(flatten)]
+    sections: Sections,
+}
+
+#[derive(serde::Serialize)]
+struct RecentVariables<'a> {
+    id: &'a str,
+    limit: usize,
+}
+
 #[derive(serde::Serialize)]
 pub struct RequestBody<'a, T: Serialize> {
     query: &'a str,
@@ -160,32 +332,104 @@ impl TryInto<UserInfo> for GraphQLResponse {
 #[derive(serde::Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 struct Data {
-    matched_user: MatchedUser,
+    // `null` when no user matches the requested username.
+    matched_user: Option<MatchedUser>,
     problems: Vec<ProblemData>,
+    // `null` for users who never took part in a contest, missing when the
+    // contest was not asked for.
+    #[serde(default)]
+    contest: Option<ContestRanking>,
+    #[serde(default)]
+    contest_history: Option<Vec<ContestHistory>>,
 }
 
 impl TryInto<UserInfo> for Data {
     type Error = core::error::Error;
 
     fn try_into(self) -> std::result::Result<UserInfo, Self::Error> {
+        let matched_user = self
+            .matched_user
+            .ok_or_else(core::error::Error::new_user_not_found)?;
+
+        let submissions = matched_user
+            .submit_stats
+            .ac_submission_num
+            .into_iter()
+            .map(|s| {
+                let problem_data = self
+                    .problems
+                    .iter()
+                    .find(|data| data.difficulty == s.difficulty)
+                    .ok_or_else(core::error::Error::new_invalid_difficulty_kind)?;
+                s.try_into_problem(problem_data)
+            })
+            .collect::<Result<Vec<super::Problem>>>()?;
+
+        // The card reads every difficulty, a partial answer is not a valid one.
+        let difficulties = [
+            super::Difficulty::All,
+            super::Difficulty::Easy,
+            super::Difficulty::Medium,
+            super::Difficulty::Hard,
+        ];
+        if !difficulties
+            .iter()
+            .all(|d| submissions.iter().any(|p| p.difficulty == *d))
+        {
+            return Err(core::error::Error::new_invalid_difficulty_kind());
+        }
+
+        let mut languages = matched_user
+            .language_problem_count
+            .into_iter()
+            .map(|language| super::Language {
+                name: language.language_name,
+                solved: language.problems_solved,
+            })
+            .collect::<Vec<super::Language>>();
+        languages.sort_by(|a, b| b.solved.cmp(&a.solved).then_with(|| a.name.cmp(&b.name)));
+
+        let tag_counts = matched_user.tag_problem_counts;
+        let mut tags = [
+            (super::TagLevel::Fundamental, tag_counts.fundamental),
+            (super::TagLevel::Intermediate, tag_counts.intermediate),
+            (super::TagLevel::Advanced, tag_counts.advanced),
+        ]
+        .into_iter()
+        .flat_map(|(level, tags)| {
+            tags.into_iter().map(move |tag| super::Tag {
+                name: tag.tag_name,
+                level,
+                solved: tag.problems_solved,
+            })
+        })
+        .collect::<Vec<super::Tag>>();
+        tags.sort_by(|a, b| b.solved.cmp(&a.solved).then_with(|| a.name.cmp(&b.name)));
+
+        // Newest first, with the badge the user chose to show ahead of them.
+        let mut badges = matched_user
+            .badges
+            .into_iter()
+            .map(super::Badge::from)
+            .collect::<Vec<super::Badge>>();
+        badges.sort_by(|a, b| b.earned.cmp(&a.earned));
+        if let Some(active) = matched_user.active_badge.map(super::Badge::from) {
+            badges.retain(|badge| *badge != active);
+            badges.insert(0, active);
+        }
+
         Ok(UserInfo {
-            username: self.matched_user.username,
-            profile: self.matched_user.profile.into(),
-            streak: self.matched_user.user_calendar.streak,
-            submissions: self
-                .matched_user
-                .submit_stats
-                .ac_submission_num
-                .into_iter()
-                .map(|s| {
-                    s.try_into_problem(
-                        self.problems
-                            .iter()
-                            .find(|data| data.difficulty == s.difficulty)
-                            .unwrap(),
-                    )
-                })
-                .collect::<Result<Vec<super::Problem>>>()?,
+            username: matched_user.username,
+            profile: matched_user.profile.into(),
+            streak: matched_user.user_calendar.streak,
+            calendar: matched_user.user_calendar.try_into()?,
+            contest: self
+                .contest
+                .map(|contest| contest.into_contest(self.contest_history.unwrap_or_default())),
+            submissions,
+            languages,
+            tags,
+            badges,
         })
     }
 }
@@ -197,6 +441,59 @@ struct MatchedUser {
     profile: Profile,
     submit_stats: SubmitStats,
     user_calendar: UserCalendar,
+    // The fields below are missing when their section was not asked for.
+    #[serde(default)]
+    language_problem_count: Vec<LanguageCount>,
+    #[serde(default)]
+    tag_problem_counts: TagProblemCounts,
+    #[serde(default)]
+    badges: Vec<Badge>,
+    #[serde(default)]
+    active_badge: Option<Badge>,
+}
+
+#[derive(serde::Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Badge {
+    name: String,
+    // Absolute, or relative to leetcode.com for the older badges.
+    icon: String,
+    creation_date: String,
+}
+
+impl From<Badge> for super::Badge {
+    fn from(value: Badge) -> super::Badge {
+        let icon = match value.icon.starts_with('/') {
+            true => format!("https://leetcode.com{}", value.icon),
+            false => value.icon,
+        };
+        super::Badge {
+            name: value.name,
+            icon,
+            earned: value.creation_date,
+        }
+    }
+}
+
+#[derive(serde::Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct LanguageCount {
+    language_name: String,
+    problems_solved: u32,
+}
+
+#[derive(serde::Deserialize, Debug, Default)]
+struct TagProblemCounts {
+    advanced: Vec<TagCount>,
+    intermediate: Vec<TagCount>,
+    fundamental: Vec<TagCount>,
+}
+
+#[derive(serde::Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct TagCount {
+    tag_name: String,
+    problems_solved: u32,
 }
 
 #[derive(serde::Deserialize, Debug)]
@@ -251,6 +548,120 @@ impl Submission {
 #[serde(rename_all = "camelCase")]
 struct UserCalendar {
     streak: u32,
+    // Missing, as the submission calendar, when the calendar was not asked
+    // for.
+    #[serde(default)]
+    active_years: Vec<u32>,
+    #[serde(default)]
+    total_active_days: u32,
+    // JSON object of submission counts keyed by the timestamp of their day,
+    // missing when the calendar was not asked for.
+    #[serde(default)]
+    submission_calendar: Option<String>,
+}
+
+impl TryInto<Calendar> for UserCalendar {
+    type Error = core::error::Error;
+
+    fn try_into(self) -> std::result::Result<Calendar, Self::Error> {
+        let calendar = self.submission_calendar.as_deref().unwrap_or("{}");
+        let days = serde_json::from_str::<BTreeMap<String, u32>>(calendar)?
+            .into_iter()
+            .map(|(timestamp, count)| {
+                let timestamp = timestamp.parse::<i64>().map_err(|e| {
+                    core::error::Error::new(core::error::ErrorKind::SerdeJson).with(e)
+                })?;
+                Ok((timestamp.div_euclid(86_400), count))
+            })
+            .collect::<Result<BTreeMap<i64, u32>>>()?;
+
+        Ok(Calendar::new(
+            days,
+            self.active_years,
+            self.total_active_days,
+            super::calendar::today(),
+        ))
+    }
+}
+
+#[derive(serde::Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct ContestRanking {
+    attended_contests_count: u32,
+    rating: f64,
+    global_ranking: u32,
+    total_participants: u32,
+    top_percentage: f64,
+}
+
+impl ContestRanking {
+    // The history lists every contest since the account was created, only
+    // the attended ones moved the rating.
+    fn into_contest(self, history: Vec<ContestHistory>) -> super::Contest {
+        super::Contest {
+            rating: self.rating,
+            global_ranking: self.global_ranking,
+            total_participants: self.total_participants,
+            top_percentage: self.top_percentage,
+            attended: self.attended_contests_count,
+            history: history
+                .into_iter()
+                .filter(|entry| entry.attended)
+                .map(|entry| super::ContestResult {
+                    title: entry.contest.title,
+                    start_time: entry.contest.start_time,
+                    rating: entry.rating,
+                    ranking: entry.ranking,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(serde::Deserialize, Debug)]
+struct ContestHistory {
+    attended: bool,
+    rating: f64,
+    ranking: u32,
+    contest: ContestData,
+}
+
+#[derive(serde::Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct ContestData {
+    title: String,
+    start_time: i64,
+}
+
+#[derive(serde::Deserialize, Debug)]
+struct RecentAcResponse {
+    data: RecentAcData,
+}
+
+#[derive(serde::Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct RecentAcData {
+    recent_ac_submission_list: Option<Vec<RecentAcSubmission>>,
+}
+
+#[derive(serde::Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct RecentAcSubmission {
+    title: String,
+    title_slug: String,
+    // Unix timestamp in seconds, as a string.
+    timestamp: String,
+}
+
+#[derive(serde::Deserialize, Debug)]
+struct DifficultiesResponse {
+    // `null` for problems that no longer exist.
+    data: BTreeMap<String, Option<QuestionDifficulty>>,
+}
+
+#[derive(serde::Deserialize, Debug)]
+struct QuestionDifficulty {
+    difficulty: String,
 }
 
 #[derive(serde::Deserialize, Debug)]
@@ -258,3 +669,292 @@ struct ProblemData {
     difficulty: String,
     count: u32,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TagLevel;
+    use core::error::ErrorKind;
+
+    const PROBLEMS: &str = r#"[
+        {"difficulty": "All", "count": 3000},
+        {"difficulty": "Easy", "count": 800},
+        {"difficulty": "Medium", "count": 1600},
+        {"difficulty": "Hard", "count": 600}
+    ]"#;
+
+    fn response(matched_user: &str) -> String {
+        format!(r#"{{"data": {{"problems": {PROBLEMS}, "matchedUser": {matched_user}}}}}"#)
+    }
+
+    fn matched_user(counts: [u32; 4]) -> String {
+        let ac = ["All", "Easy", "Medium", "Hard"]
+            .iter()
+            .zip(counts)
+            .map(|(d, c)| format!(r#"{{"difficulty": "{d}", "count": {c}, "submissions": {c}}}"#))
+            .collect::<Vec<String>>()
+            .join(",");
+
+        format!(
+            r#"{{
+                "username": "newcomer",
+                "profile": {{
+                    "realname": "", "about": "", "avatar": "", "skills": [],
+                    "country": null, "ranking": 5000000
+                }},
+                "submitStats": {{"acSubmissionNum": [{ac}]}},
+                "languageProblemCount": [],
+                "tagProblemCounts": {{"advanced": [], "intermediate": [], "fundamental": []}},
+                "badges": [], "activeBadge": null,
+                "userCalendar": {{
+                    "streak": 0, "activeYears": [], "totalActiveDays": 0,
+                    "submissionCalendar": "{{}}"
+                }}
+            }}"#
+        )
+    }
+
+    #[test]
+    fn zero_stats_user_is_not_an_error() {
+        let user_info = parse_response(response(&matched_user([0; 4])).as_bytes()).unwrap();
+
+        assert_eq!(user_info.username, "newcomer");
+        assert_eq!(user_info.problems_stats(), (0, 3000));
+    }
+
+    #[test]
+    fn unknown_user_is_not_found() {
+        let body = format!(
+            r#"{{"data": {{"problems": {PROBLEMS}, "matchedUser": null}}, "errors": [{{"message": "That user does not exist."}}]}}"#
+        );
+        let error = parse_response(body.as_bytes()).unwrap_err();
+
+        assert!(matches!(error.kind(), ErrorKind::UserNotFound));
+        assert_eq!(error.status(), 404);
+    }
+
+    #[test]
+    fn malformed_response_is_a_parse_error() {
+        let error = parse_response(b"<html>rate limited</html>").unwrap_err();
+
+        assert!(matches!(error.kind(), ErrorKind::SerdeJson));
+    }
+
+    #[test]
+    fn submission_calendar_is_counted_per_day() {
+        let calendar = r#""streak": 0, "activeYears": [], "totalActiveDays": 0,
+                    "submissionCalendar": "{}""#;
+        let body = response(&matched_user([0; 4]).replace(
+            calendar,
+            r#""streak": 2, "activeYears": [2023, 2024], "totalActiveDays": 2,
+                    "submissionCalendar": "{\"1718409600\": 3, \"1718496000\": 1}""#,
+        ));
+        let user_info = parse_response(body.as_bytes()).unwrap();
+
+        assert_eq!(user_info.calendar.count(19_889), 3);
+        assert_eq!(user_info.calendar.count(19_890), 1);
+        assert_eq!(user_info.calendar.count(19_891), 0);
+        assert_eq!(user_info.calendar.active_years(), [2023, 2024]);
+        assert_eq!(user_info.calendar.total_active_days(), 2);
+    }
+
+    #[test]
+    fn contest_history_keeps_attended_contests() {
+        let body = response(&matched_user([0; 4])).replace(
+            r#""matchedUser""#,
+            r#""contest": {
+                "attendedContestsCount": 2, "rating": 1612.5, "globalRanking": 52000,
+                "totalParticipants": 600000, "topPercentage": 8.67
+            },
+            "contestHistory": [
+                {"attended": true, "rating": 1500, "ranking": 3000, "contest": {"title": "Weekly Contest 1", "startTime": 1700000000}},
+                {"attended": false, "rating": 1500, "ranking": 0, "contest": {"title": "Weekly Contest 2", "startTime": 1700600000}},
+                {"attended": true, "rating": 1612.5, "ranking": 1200, "contest": {"title": "Weekly Contest 3", "startTime": 1701200000}}
+            ],
+            "matchedUser""#,
+        );
+        let contest = parse_response(body.as_bytes()).unwrap().contest.unwrap();
+
+        assert_eq!(contest.attended, 2);
+        assert_eq!(contest.global_ranking, 52000);
+        let titles = contest
+            .history
+            .iter()
+            .map(|c| c.title.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(titles, ["Weekly Contest 1", "Weekly Contest 3"]);
+    }
+
+    #[test]
+    fn users_without_contests_have_no_contest() {
+        let body = response(&matched_user([0; 4])).replace(
+            r#""matchedUser""#,
+            r#""contest": null, "contestHistory": null, "matchedUser""#,
+        );
+
+        assert!(parse_response(body.as_bytes()).unwrap().contest.is_none());
+    }
+
+    #[test]
+    fn sections_not_asked_for_are_empty() {
+        let body = response(
+            &matched_user([0; 4])
+                .replace(
+                    r#""languageProblemCount": [],
+                "tagProblemCounts": {"advanced": [], "intermediate": [], "fundamental": []},
+                "badges": [], "activeBadge": null,"#,
+                    "",
+                )
+                .replace(
+                    r#", "activeYears": [], "totalActiveDays": 0,
+                    "submissionCalendar": "{}""#,
+                    "",
+                ),
+        );
+        let user_info = parse_response(body.as_bytes()).unwrap();
+
+        assert!(user_info.languages.is_empty());
+        assert!(user_info.tags.is_empty());
+        assert!(user_info.badges.is_empty());
+        assert!(user_info.contest.is_none());
+        assert_eq!(user_info.calendar.count(19_889), 0);
+    }
+
+    #[test]
+    fn sections_are_sent_as_include_variables() {
+        let variables = UserVariables {
+            id: "newcomer",
+            sections: Sections {
+                skills: true,
+                ..Default::default()
+            },
+        };
+
+        assert_eq!(
+            serde_json::to_value(variables).unwrap(),
+            serde_json::json!({
+                "id": "newcomer",
+                "calendar": false,
+                "contest": false,
+                "skills": true,
+                "badges": false
+            })
+        );
+    }
+
+    #[test]
+    fn languages_and_tags_are_sorted_by_solved_problems() {
+        let body = response(&matched_user([0; 4]).replace(
+            r#""languageProblemCount": [],
+                "tagProblemCounts": {"advanced": [], "intermediate": [], "fundamental": []},"#,
+            r#""languageProblemCount": [
+                    {"languageName": "C++", "problemsSolved": 12},
+                    {"languageName": "Rust", "problemsSolved": 40},
+                    {"languageName": "Python3", "problemsSolved": 12}
+                ],
+                "tagProblemCounts": {
+                    "advanced": [{"tagName": "Dynamic Programming", "problemsSolved": 20}],
+                    "intermediate": [{"tagName": "Hash Table", "problemsSolved": 30}],
+                    "fundamental": [{"tagName": "Array", "problemsSolved": 45}]
+                },"#,
+        ));
+        let user_info = parse_response(body.as_bytes()).unwrap();
+
+        let languages = user_info
+            .languages
+            .iter()
+            .map(|l| l.name.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(languages, ["Rust", "C++", "Python3"]);
+        let tags = user_info
+            .tags
+            .iter()
+            .map(|t| (t.name.as_str(), t.level))
+            .collect::<Vec<(&str, TagLevel)>>();
+        assert_eq!(
+            tags,
+            [
+                ("Array", TagLevel::Fundamental),
+                ("Hash Table", TagLevel::Intermediate),
+                ("Dynamic Programming", TagLevel::Advanced),
+            ]
+        );
+    }
+
+    #[test]
+    fn active_badge_comes_before_the_newest_ones() {
+        let body = response(&matched_user([0; 4]).replace(
+            r#""badges": [], "activeBadge": null,"#,
+            r#""badges": [
+                    {"name": "50 Days Badge 2023", "icon": "/static/images/badges/2023/lg/2023-50.png", "creationDate": "2023-03-01"},
+                    {"name": "Knight", "icon": "https://assets.leetcode.com/static_assets/knight.gif", "creationDate": "2024-01-20"},
+                    {"name": "Jan LeetCoding Challenge", "icon": "https://assets.leetcode.com/static_assets/jan.png", "creationDate": "2024-02-01"}
+                ],
+                "activeBadge": {"name": "Knight", "icon": "https://assets.leetcode.com/static_assets/knight.gif", "creationDate": "2024-01-20"},"#,
+        ));
+        let user_info = parse_response(body.as_bytes()).unwrap();
+
+        let badges = user_info
+            .badges
+            .iter()
+            .map(|b| b.name.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(
+            badges,
+            ["Knight", "Jan LeetCoding Challenge", "50 Days Badge 2023"]
+        );
+        assert_eq!(
+            user_info.badges[2].icon,
+            "https://leetcode.com/static/images/badges/2023/lg/2023-50.png"
+        );
+    }
+
+    #[test]
+    fn recent_submissions_get_their_difficulty() {
+        let list = br#"{"data": {"recentAcSubmissionList": [
+            {"title": "Two Sum", "titleSlug": "two-sum", "timestamp": "1718450000"},
+            {"title": "Gone", "titleSlug": "gone", "timestamp": "1718400000"},
+            {"title": "Median of Two Sorted Arrays", "titleSlug": "median-of-two-sorted-arrays", "timestamp": "1718300000"}
+        ]}}"#;
+        let submissions = parse_recent_ac(list).unwrap();
+
+        let query = difficulties_query(&submissions);
+        assert!(query.contains(r#"q0: question(titleSlug: "two-sum") { difficulty }"#));
+        assert!(query.contains(r#"q2: question(titleSlug: "median-of-two-sorted-arrays")"#));
+
+        let difficulties = br#"{"data": {"q0": {"difficulty": "Easy"}, "q1": null, "q2": {"difficulty": "Hard"}}}"#;
+        let submissions = with_difficulties(submissions, difficulties).unwrap();
+        let difficulties = submissions.iter().map(|s| s.difficulty).collect::<Vec<_>>();
+        assert_eq!(
+            difficulties,
+            [Some(Difficulty::Easy), None, Some(Difficulty::Hard)]
+        );
+        assert_eq!(submissions[0].timestamp, 1_718_450_000);
+    }
+
+    #[test]
+    fn recent_submissions_of_unknown_user_are_not_found() {
+        let error = parse_recent_ac(br#"{"data": {"recentAcSubmissionList": null}}"#).unwrap_err();
+
+        assert!(matches!(error.kind(), ErrorKind::UserNotFound));
+    }
+
+    #[test]
+    fn malformed_calendar_is_a_parse_error() {
+        let body = response(&matched_user([0; 4]).replace(
+            r#""submissionCalendar": "{}""#,
+            r#""submissionCalendar": "[]""#,
+        ));
+        let error = parse_response(body.as_bytes()).unwrap_err();
+
+        assert!(matches!(error.kind(), ErrorKind::SerdeJson));
+    }
+
+    #[test]
+    fn partial_stats_are_a_parse_error() {
+        let body = response(&matched_user([0; 4]).replace(r#""Hard""#, r#""Impossible""#));
+        let error = parse_response(body.as_bytes()).unwrap_err();
+
+        assert!(matches!(error.kind(), ErrorKind::InvalidDifficultyKind));
+    }
+}
@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use core::error::{Error, Result};
+
+// Badge icons already fetched by this process, as data URIs keyed by URL.
+// Failures are not kept, a transient one would otherwise hide the icon until
+// the process restarts. LeetCode has a few hundred badges at most, so nothing
+// is ever evicted.
+static CACHE: OnceLock<Mutex<HashMap<String, Arc<str>>>> = OnceLock::new();
+
+// Largest icon inlined in the card, LeetCode badges weigh a few kilobytes.
+const MAX_ICON_SIZE: usize = 256 * 1024;
+
+// Looks the icon at `url` up in the cache and only fetches it when missing.
+pub async fn load(url: &str) -> Result<Arc<str>> {
+    let cache = CACHE.get_or_init(Default::default);
+    if let Some(icon) = cache.lock().unwrap().get(url) {
+        return Ok(icon.clone());
+    }
+
+    let icon = Arc::<str>::from(fetch(url).await?);
+    cache.lock().unwrap().insert(url.to_string(), icon.clone());
+
+    Ok(icon)
+}
+
+async fn fetch(url: &str) -> Result<String> {
+    let resp = reqwest::get(url).await?.error_for_status()?;
+    // The length is checked before and after reading, the header may be
+    // missing or wrong.
+    if resp
+        .content_length()
+        .is_some_and(|length| length > MAX_ICON_SIZE as u64)
+    {
+        return Err(too_large(url));
+    }
+    let content_type = resp
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let bytes = resp.bytes().await?;
+    if bytes.len() > MAX_ICON_SIZE {
+        return Err(too_large(url));
+    }
+
+    Ok(data_uri(content_type.as_deref(), url, &bytes))
+}
+
+fn too_large(url: &str) -> Error {
+    Error::new_reqwest_too_large().with(format!(
+        "icon at {url} is larger than {MAX_ICON_SIZE} bytes"
+    ))
+}
+
+// Inlines `bytes` so that the card stays self-contained. The type is taken
+// from the response, or guessed from the URL when it is not an image one.
+fn data_uri(content_type: Option<&str>, url: &str, bytes: &[u8]) -> String {
+    use base64::Engine;
+
+    let mime = content_type
+        .map(|value| value.split(';').next().unwrap_or_default().trim())
+        .filter(|mime| mime.starts_with("image/"))
+        .unwrap_or_else(|| guess_mime(url));
+    format!(
+        "data:{mime};base64,{}",
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    )
+}
+
+fn guess_mime(url: &str) -> &'static str {
+    let path = url.split(['?', '#']).next().unwrap_or_default();
+    let extension = path
+        .rsplit_once('.')
+        .map(|(_, ext)| ext.to_ascii_lowercase());
+    match extension.as_deref() {
+        Some("svg") => "image/svg+xml",
+        Some("gif") => "image/gif",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("webp") => "image/webp",
+        _ => "image/png",
+    }
+}
+
+// Fills the cache as if `url` had been fetched, for tests that stay offline.
+#[cfg(test)]
+pub fn insert(url: &str, data_uri: &str) {
+    CACHE
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap()
+        .insert(url.to_string(), data_uri.into());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn icons_are_inlined_with_their_type() {
+        assert_eq!(
+            data_uri(
+                Some("image/svg+xml; charset=utf-8"),
+                "https://x/a.png",
+                b"<svg/>"
+            ),
+            "data:image/svg+xml;base64,PHN2Zy8+"
+        );
+        assert_eq!(
+            data_uri(
+                Some("application/octet-stream"),
+                "https://x/a.GIF?v=2",
+                b"GIF"
+            ),
+            "data:image/gif;base64,R0lG"
+        );
+        assert_eq!(
+            data_uri(None, "https://x/badge", b"PNG"),
+            "data:image/png;base64,UE5H"
+        );
+    }
+
+    #[test]
+    fn cached_icons_are_not_fetched_again() {
+        let url = "https://leetcode.invalid/cached.png";
+        insert(url, "data:image/png;base64,UE5H");
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let icon = runtime.block_on(load(url)).unwrap();
+        assert_eq!(&*icon, "data:image/png;base64,UE5H");
+    }
+
+    #[test]
+    fn failed_icons_are_not_cached() {
+        let url = "not a url";
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+
+        assert!(runtime.block_on(load(url)).is_err());
+        assert!(!CACHE.get().unwrap().lock().unwrap().contains_key(url));
+    }
+
+    #[test]
+    fn oversized_icons_have_their_own_error() {
+        let error = too_large("https://x/a.png");
+        assert!(matches!(
+            error.kind(),
+            core::error::ErrorKind::Reqwest(core::error::Reqwest::TooLarge)
+        ));
+    }
+}
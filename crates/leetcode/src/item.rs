@@ -1,7 +1,12 @@
-use core::item::Item;
-use core::{attribute, style};
+use core::item::{FontStyle, Item};
+use core::metrics::Metrics;
+use core::style;
+use core::theme::Theme;
 
-use super::{Difficulty, Problem, UserInfo};
+use super::calendar::{self, Calendar};
+use super::{
+    font, layout::Layout, Badge, Contest, ContestResult, Difficulty, Problem, RecentSubmission,
+};
 
 const ICON_PATH: [&str; 3] = [
     "M67.506,83.066 C70.000,80.576 74.037,80.582 76.522,83.080 C79.008,85.578 79.002,89.622 76.508,92.112 L65.435,103.169 C55.219,113.370 38.560,113.518 28.172,103.513 C28.112,103.455 23.486,98.920 8.227,83.957 C-1.924,74.002 -2.936,58.074 6.616,47.846 L24.428,28.774 C33.910,18.621 51.387,17.512 62.227,26.278 L78.405,39.362 C81.144,41.577 81.572,45.598 79.361,48.342 C77.149,51.087 73.135,51.515 70.395,49.300 L54.218,36.217 C48.549,31.632 38.631,32.262 33.739,37.500 L15.927,56.572 C11.277,61.552 11.786,69.574 17.146,74.829 C28.351,85.816 36.987,94.284 36.997,94.294 C42.398,99.495 51.130,99.418 56.433,94.123 L67.506,83.066 Z",
@@ -9,349 +14,703 @@ const ICON_PATH: [&str; 3] = [
     "M40.606,72.001 C37.086,72.001 34.231,69.142 34.231,65.614 C34.231,62.087 37.086,59.228 40.606,59.228 L87.624,59.228 C91.145,59.228 94,62.087 94,65.614 C94,69.142 91.145,72.001 87.624,72.001 L40.606,72.001 Z"
 ];
 
-pub fn root(width: u32, height: u32, user_info: &UserInfo) -> Item {
-    let attr = attribute!({
-        "id": "root",
-        "width": format!("{}", width),
-        "height": format!("{}", height),
-        "viewBox": format!("0 0 {} {}", width, height),
-        "version": "1.1",
-        "xmlns": "http://www.w3.org/2000/svg",
-        "xmlns:xlink": "http://www.w3.org/1999/xlink"
-    });
-    let style = style! {
-        "fill": "none"
-    };
-    let backgroud_style = style! {
-        "transform": "translate(0.5px, 0.5px)",
-        "stroke": "var(--bg-2)",
-        "fill": "var(--bg-0)",
-        "stroke-width": 1,
-        "width": format!("{}px", width - 1),
-        "height": format!("{}px", height - 1),
-        "rx": "4px",
-    };
+pub fn root(layout: &Layout, title: &str) -> Item {
+    let (width, height) = (layout.width(), layout.height());
+    let background = Item::rect(0.5, 0.5, (width - 1) as f64, (height - 1) as f64)
+        .id("background")
+        .attr("rx", 4)
+        .style(style! {
+            "stroke": "var(--bg-2)",
+            "fill": "var(--bg-0)",
+            "stroke-width": 1,
+        });
 
-    let childs = vec![
-        Item::new("title", None, None, None, None, Some(format!("{} | LeetCode Stat Card", user_info.username))),
-        Item::new("style", Some(attribute!({"id": "default-colors"})), None, None, None, Some(String::from("svg{opacity:0}:root{--bg-0:#fff;--bg-1:#e5e5e5;--bg-2:#d3d3d3;--bg-3:#d3d3d3;--text-0:#000;--text-1:#808080;--text-2:#808080;--text-3:#808080;--color-0:#ffa116;--color-1:#5cb85c;--color-2:#f0ad4e;--color-3:#d9534f}"))),
-        Item::new("rect", Some(attribute!({"id": "background"})), Some(backgroud_style), Some(true), None, None),
-    ];
+    Item::svg(width, height)
+        .id("root")
+        .style(style! {
+            "fill": "none"
+        })
+        .child(Item::title(title))
+        .child(
+            Item::stylesheet(format!(
+                "svg{{opacity:0}}:root{{{}}}",
+                Theme::default().format_vars().concat()
+            ))
+            .id("default-colors"),
+        )
+        .child(background)
+}
+
+pub fn icon(layout: &Layout) -> Item {
+    let (x, y, scale) = layout.icon();
+    let paths = Item::g()
+        .style(style! {
+            "stroke": "none",
+            "fill": "var(--text-0)",
+            "fill-rule": "evenodd",
+        })
+        .child(Item::path(ICON_PATH[0]).id("C").style(style! {
+            "fill": "#FFA116",
+            "fill-rule": "nonzero"
+        }))
+        .child(Item::path(ICON_PATH[1]).id("L").fill("#000000"))
+        .child(Item::path(ICON_PATH[2]).id("dash").fill("#B3B3B3"));
 
-    Item::new("svg", Some(attr), Some(style), None, Some(childs), None)
+    Item::g()
+        .id("icon")
+        .translate(x, y)
+        .scale(scale)
+        .child(paths)
 }
 
-pub fn icon() -> Item {
-    let style = style! {
-        "stroke": "none",
-        "fill": "var(--text-0)",
-        "fill-rule": "evenodd",
-    };
-    let child_1_attr = attribute!({
-        "id": "C",
-        "d": ICON_PATH[0],
-    });
-    let child_1_style = style! {
-        "fill": "#FFA116",
-        "fill-rule": "nonzero"
-    };
-    let child_2_attr = attribute!({
-        "id": "L",
-        "d": ICON_PATH[1]
-    });
-    let child_2_style = style! {
-        "fill": "#000000",
-    };
-    let child_3_attr = attribute!({
-        "id": "dash",
-        "d": ICON_PATH[2]
-    });
-    let child_3_style = style! {
-        "fill": "#B3B3B3",
-    };
+pub const BOLD: FontStyle = FontStyle {
+    weight: 700,
+    italic: false,
+};
 
-    let icon_path = Item::new(
-        "g",
-        None,
-        Some(style),
-        None,
-        Some(vec![
-            Item::new(
-                "path",
-                Some(child_1_attr),
-                Some(child_1_style),
-                None,
-                None,
-                None,
-            ),
-            Item::new(
-                "path",
-                Some(child_2_attr),
-                Some(child_2_style),
-                None,
-                None,
-                None,
-            ),
-            Item::new(
-                "path",
-                Some(child_3_attr),
-                Some(child_3_style),
-                None,
-                None,
-                None,
-            ),
-        ]),
-        None,
-    );
+const RANKING_SIZE: u32 = 18;
+const HEADER_GAP: f64 = 16.0;
+const BREAKDOWN_GAP: f64 = 8.0;
 
-    let attr = attribute!({"id": "icon"});
+// The username is shrunk, then truncated, to fit in the space left by the
+// ranking.
+pub fn username(
+    layout: &Layout,
+    username: &str,
+    ranking: u32,
+    heading: &Metrics,
+    number: &Metrics,
+) -> Item {
+    let ranking_width = number.width(&format!("#{ranking}"), RANKING_SIZE as f64);
+    let max_width = layout.ranking_x() - ranking_width - HEADER_GAP - layout.title_x();
+    let (text, size) = heading.fit(username, 24.0, 16.0, max_width);
 
-    let style = style! {
-        "transform": "translate(20px, 15px) scale(0.27)",
-    };
+    let text = Item::text(text)
+        .id("username-text")
+        .fill("var(--text-0)")
+        .font(size as u32, true)
+        .font_family(font::HEADING);
 
-    Item::new(
-        "g",
-        Some(attr),
-        Some(style),
-        None,
-        Some(vec![icon_path]),
-        None,
-    )
+    Item::a(&format!("https://leetcode.com/{username}/"))
+        .id("username")
+        .translate(layout.title_x(), layout.header_y())
+        .child(text)
 }
 
-pub fn username(username: &str) -> Item {
-    let attr = attribute!({
-        "id": "username",
-        "href": format!("https://leetcode.com/{username}/"),
-        "target": "_blank"
-    });
-    let style = style! {
-        "transform": "translate(65px, 40px)",
-    };
+pub fn ranking(layout: &Layout, ranking: u32) -> Item {
+    Item::text(format!("#{ranking}"))
+        .id("ranking")
+        .translate(layout.ranking_x(), layout.header_y())
+        .fill("var(--text-1)")
+        .font(RANKING_SIZE, true)
+        .font_family(font::NUMBER)
+        .anchor("end")
+}
 
-    let child_attr = attribute!({
-        "id": "username-text",
-    });
-    let child_style = style! {
-        "fill": "var(--text-0)",
-        "font-size": "24px",
-        "font-weight": "bold",
+pub fn total_solved(layout: &Layout, solved: u32, total: u32) -> Item {
+    let r = layout.ring_radius();
+    let circle_bg = Item::circle(r, r, r)
+        .id("total-solved-bg")
+        .stroke("var(--bg-1)", 6.0);
+    let circle_ring = Item::circle(r, r, r)
+        .id("total-solved-ring")
+        .rotate(-90.0, r, r)
+        .stroke("var(--color-0)", 6.0)
+        .style(style! {
+            "stroke-dasharray": format!("{} 10000", 2.0 * r * std::f64::consts::PI * solved as f64 / total as f64),
+            "stroke-linecap": "round"
+        });
+    let text = Item::text(format!("{solved}"))
+        .id("total-solved-text")
+        .translate(r, r)
+        .fill("var(--text-0)")
+        .font(28, true)
+        .font_family(font::NUMBER)
+        .anchor("middle")
+        .style(style! {
+            "alignment-baseline": "central",
+            "dominant-baseline": "central"
+        });
+
+    let (x, y) = layout.ring();
+    Item::g()
+        .id("total-solved")
+        .translate(x, y)
+        .children([circle_bg, circle_ring, text])
+}
+
+pub fn solved(layout: &Layout, problems: &[Problem]) -> Item {
+    let length = layout.bar_length();
+    let childs = [
+        (Difficulty::Easy, "var(--color-1)"),
+        (Difficulty::Medium, "var(--color-2)"),
+        (Difficulty::Hard, "var(--color-3)"),
+    ]
+    .iter()
+    .enumerate()
+    .map(|(i, (d, c))| {
+        let problem = problems.iter().find(|p| p.difficulty == *d).unwrap();
+        let pb_type = Item::text(d.capitalize())
+            .id(&format!("{}-solved-type", d))
+            .fill("var(--text-1)")
+            .font(18, true);
+        let count = Item::text(format!("{} / {}", problem.count, problem.total))
+            .id(&format!("{}-solved-count", d))
+            .translate(length, 0.0)
+            .fill("var(--text-1)")
+            .font(16, true)
+            .font_family(font::NUMBER)
+            .anchor("end");
+        let line_bg = Item::line(0.0, 10.0, length, 10.0)
+            .id(&format!("{}-solved-bg", d))
+            .stroke("var(--bg-1)", 4.0)
+            .style(style! {
+                "stroke-linecap": "round"
+            });
+        let line_progress = Item::line(0.0, 10.0, length, 10.0)
+            .id(&format!("{}-solved-progress", d))
+            .stroke(c, 4.0)
+            .style(style! {
+                "stroke-dasharray": format!("{} 10000", length * problem.count as f64 / problem.total as f64),
+                "stroke-linecap": "round"
+            });
+
+        Item::g()
+            .id(&format!("{}-solved", d))
+            .translate(0.0, layout.row_spacing() * i as f64)
+            .children([pb_type, count, line_bg, line_progress])
+    })
+    .collect::<Vec<Item>>();
+
+    let (x, y) = layout.rows();
+    Item::g().id("solved").translate(x, y).children(childs)
+}
+
+// Extra stats of the wide card, as label and value rows aligned on the
+// difficulty rows.
+pub fn stats(layout: &Layout, stats: &[(&str, String)]) -> Item {
+    let width = layout.stats_width();
+    let childs = stats
+        .iter()
+        .enumerate()
+        .map(|(i, (label, value))| {
+            let id = label.to_ascii_lowercase();
+            let label = Item::text(*label)
+                .id(&format!("{id}-stat-label"))
+                .fill("var(--text-1)")
+                .font(16, true);
+            let value = Item::text(value.as_str())
+                .id(&format!("{id}-stat-value"))
+                .translate(width, 0.0)
+                .fill("var(--text-0)")
+                .font(16, true)
+                .font_family(font::NUMBER)
+                .anchor("end");
+
+            Item::g()
+                .id(&format!("{id}-stat"))
+                .translate(0.0, layout.row_spacing() * i as f64)
+                .children([label, value])
+        })
+        .collect::<Vec<Item>>();
+
+    let (x, y) = layout.stats();
+    Item::g().id("stats").translate(x, y).children(childs)
+}
+
+// Opacity of `--color-0` for each level of activity, days without any are
+// drawn with `--bg-1`.
+const HEATMAP_LEVELS: [f64; 4] = [0.3, 0.55, 0.8, 1.0];
+
+// GitHub-style heatmap of the past year, one column per week starting on
+// Sunday, the month labels above and the active days right-aligned with them.
+pub fn heatmap(layout: &Layout, calendar: &Calendar, body: &Metrics) -> Item {
+    let (x, y, pitch) = layout.heatmap();
+    let size = (pitch * 0.8 * 100.0).round() / 100.0;
+    let first_day = calendar.first_day();
+    let right = pitch * (calendar::WEEKS - 1) as f64 + size;
+
+    let active_days = match calendar.total_active_days() {
+        1 => "1 active day".to_string(),
+        days => format!("{days} active days"),
     };
+    let summary = match calendar.active_years().iter().min() {
+        Some(year) => format!("{active_days} since {year}"),
+        None => active_days,
+    };
+    let (summary, summary_size) = body.fit(&summary, 10.0, 10.0, right);
+    let summary_left = right - body.width(&summary, summary_size);
+
+    let levels = HEATMAP_LEVELS
+        .iter()
+        .enumerate()
+        .map(|(i, opacity)| {
+            format!(
+                "#heatmap .level-{}{{fill:var(--color-0);fill-opacity:{opacity}}}",
+                i + 1
+            )
+        })
+        .collect::<String>();
+    let mut childs = vec![Item::stylesheet(format!(
+        "#heatmap rect{{fill:var(--bg-1)}}{levels}"
+    ))];
 
-    let child = Item::new(
-        "text",
-        Some(child_attr),
-        Some(child_style),
-        None,
-        None,
-        Some(username.to_string()),
+    let mut last_label = None;
+    for week in 0..calendar::WEEKS {
+        let (_, month, day) = calendar::civil(first_day + week * 7);
+        // A month is labeled above its first full week, when there is room.
+        let label = calendar::month_name(month);
+        let label_x = pitch * week as f64;
+        if day <= 7
+            && label_x + body.width(label, 10.0) + pitch < summary_left
+            && last_label.is_none_or(|last| week - last > 2)
+        {
+            childs.push(
+                Item::text(label)
+                    .translate(label_x, -4.0)
+                    .fill("var(--text-1)")
+                    .font(10, false),
+            );
+            last_label = Some(week);
+        }
+    }
+    childs.push(
+        Item::text(summary)
+            .id("heatmap-summary")
+            .translate(right, -4.0)
+            .fill("var(--text-1)")
+            .font(summary_size as u32, false)
+            .anchor("end"),
     );
 
-    Item::new("a", Some(attr), Some(style), None, Some(vec![child]), None)
+    let days = (first_day..=calendar.today()).map(|day| {
+        let week = (day - first_day) / 7;
+        let cell = Item::rect(
+            pitch * week as f64,
+            pitch * calendar::weekday(day) as f64,
+            size,
+            size,
+        )
+        .attr("rx", (size / 5.0).round());
+        match calendar.level(day) {
+            0 => cell,
+            level => cell.attr("class", format!("level-{level}")),
+        }
+    });
+    childs.extend(days);
+
+    Item::g().id("heatmap").translate(x, y).children(childs)
 }
 
-pub fn ranking(ranking: u32) -> Item {
-    let attr = attribute!({
-        "id": "ranking",
+// Current rating and standing, left of the rating chart.
+pub fn contest(layout: &Layout, contest: &Contest) -> Item {
+    let center = layout.body_center();
+    let rating = Item::text(format!("{:.0}", contest.rating))
+        .id("contest-rating")
+        .translate(0.0, center - 18.0)
+        .fill("var(--text-0)")
+        .font(28, true)
+        .font_family(font::NUMBER);
+    let rows = [
+        ("contest-top", format!("Top {:.2}%", contest.top_percentage)),
+        ("contest-ranking", format!("#{}", contest.global_ranking)),
+        (
+            "contest-attended",
+            match contest.attended {
+                1 => "1 contest".to_string(),
+                attended => format!("{attended} contests"),
+            },
+        ),
+    ]
+    .into_iter()
+    .enumerate()
+    .map(|(i, (id, text))| {
+        Item::text(text)
+            .id(id)
+            .translate(0.0, center + 8.0 + 20.0 * i as f64)
+            .fill("var(--text-1)")
+            .font(14, true)
     });
-    let style = style! {
-        "transform": "translate(480px, 40px)",
-        "fill": "var(--text-1)",
-        "font-size": "18px",
-        "font-weight": "bold",
-        "text-anchor": "end"
-    };
 
-    Item::new(
-        "text",
-        Some(attr),
-        Some(style),
-        None,
-        None,
-        Some(format!("#{ranking}")),
-    )
+    Item::g()
+        .id("contest")
+        .translate(layout.ring().0, 0.0)
+        .child(rating)
+        .children(rows)
 }
 
-pub fn total_solved(solved: u32, total: u32) -> Item {
-    let attr = attribute!({
-        "id": "total-solved",
-    });
-    let style = style! {
-        "transform": "translate(30px, 85px)"
+// Line and area chart of the rating after each contest, over time, with the
+// peak rating and the dates of the first and last contests.
+pub fn rating_chart(layout: &Layout, history: &[ContestResult]) -> Item {
+    let (x, y, width, height) = layout.chart();
+    let baseline = Item::line(0.0, height, width, height)
+        .id("rating-chart-baseline")
+        .stroke("var(--bg-1)", 1.0);
+    let chart = Item::g().id("rating-chart").translate(x, y).child(baseline);
+    let (Some(first), Some(last)) = (history.first(), history.last()) else {
+        return chart;
     };
 
-    let circle_bg_attr = attribute!({
-        "id": "total-solved-bg"
+    let (low, high) = history.iter().fold((f64::MAX, f64::MIN), |(low, high), c| {
+        (low.min(c.rating), high.max(c.rating))
     });
-    let circle_bg_style = style! {
-        "cx": "40px",
-        "cy": "40px",
-        "r": "40px",
-        "stroke": "var(--bg-1)",
-        "stroke-width": "6px"
+    let margin = ((high - low) * 0.1).max(10.0);
+    let (low, high) = (low - margin, high + margin);
+    let span = (last.start_time - first.start_time).max(1) as f64;
+    let round = |v: f64| (v * 10.0).round() / 10.0;
+    let points = history
+        .iter()
+        .map(|c| {
+            let px = match history.len() {
+                1 => width / 2.0,
+                _ => (c.start_time - first.start_time) as f64 / span * width,
+            };
+            let py = height - (c.rating - low) / (high - low) * height;
+            (round(px), round(py))
+        })
+        .collect::<Vec<(f64, f64)>>();
+
+    let line = points
+        .iter()
+        .map(|(px, py)| format!("{px},{py}"))
+        .collect::<Vec<String>>()
+        .join("L");
+    let (first_x, _) = points[0];
+    let (last_x, last_y) = points[points.len() - 1];
+    let area = Item::path(&format!("M{first_x},{height}L{line}L{last_x},{height}Z"))
+        .id("rating-chart-area")
+        .fill("var(--color-0)")
+        .style(style! {
+            "fill-opacity": 0.15
+        });
+    let stroke = Item::path(&format!("M{line}"))
+        .id("rating-chart-line")
+        .stroke("var(--color-0)", 2.0)
+        .style(style! {
+            "stroke-linejoin": "round",
+            "stroke-linecap": "round"
+        });
+    let dot = Item::circle(last_x, last_y, 3.0)
+        .id("rating-chart-dot")
+        .fill("var(--color-0)");
+
+    let label = |text: String, x: f64, y: f64, anchor: &str| {
+        Item::text(text)
+            .translate(x, y)
+            .fill("var(--text-1)")
+            .font(10, true)
+            .anchor(anchor)
     };
-    let circle_ring_attr = attribute!({
-        "id": "total-solved-ring"
-    });
-    let circle_ring_style = style! {
-        "cx": "40px",
-        "cy": "40px",
-        "r": "40px",
-        "transform": "rotate(-90deg)",
-        "transform-origin": "40px 40px",
-        "stroke-dasharray": format!("{} 10000", 80.0 * std::f64::consts::PI * solved as f64 / total as f64),
-        "stroke": "var(--color-0)",
-        "stroke-width": "6px",
-        "stroke-linecap": "round"
-    };
-    let text_attr = attribute!({
-        "id": "total-solved-text"
-    });
-    let text_style = style! {
-        "transform": "translate(40px, 40px)",
-        "font-size": "28px",
-        "alignment-baseline": "central",
-        "dominant-baseline": "central",
-        "text-anchor": "middle",
-        "fill": "var(--text-0)",
-        "font-weight": "bold"
+    let date = |c: &ContestResult| {
+        let (year, month, _) = calendar::civil(c.start_time.div_euclid(86_400));
+        format!("{} {year}", calendar::month_name(month))
     };
+    let peak = history.iter().map(|c| c.rating).fold(f64::MIN, f64::max);
+    let mut labels = vec![
+        label(format!("Peak {peak:.0}"), width, -4.0, "end").id("rating-chart-peak"),
+        label(date(first), 0.0, height + 14.0, "start").id("rating-chart-start"),
+    ];
+    if history.len() > 1 {
+        labels.push(label(date(last), width, height + 14.0, "end").id("rating-chart-end"));
+    }
 
-    let circle_bg = Item::new(
-        "circle",
-        Some(circle_bg_attr),
-        Some(circle_bg_style),
-        None,
-        None,
-        None,
-    );
-    let circle_ring = Item::new(
-        "circle",
-        Some(circle_ring_attr),
-        Some(circle_ring_style),
-        None,
-        None,
-        None,
-    );
-    let text = Item::new(
-        "text",
-        Some(text_attr),
-        Some(text_style),
-        None,
-        None,
-        Some(format!("{solved}")),
-    );
+    chart.children([area, stroke, dot]).children(labels)
+}
 
-    Item::new(
-        "g",
-        Some(attr),
-        Some(style),
-        None,
-        Some(vec![circle_bg, circle_ring, text]),
-        None,
-    )
+pub fn no_contest(layout: &Layout) -> Item {
+    Item::text("No contest attended yet")
+        .id("contest")
+        .translate(layout.width() as f64 / 2.0, layout.body_center())
+        .fill("var(--text-1)")
+        .font(16, false)
+        .anchor("middle")
+        .style(style! {
+            "dominant-baseline": "central"
+        })
 }
 
-pub fn solved(problems: &[Problem]) -> Item {
-    let attr = attribute!({
-        "id": "solved",
-    });
-    let style = style! {
-        "transform": "translate(160px, 80px)"
-    };
+// Column of `rows` as name, solved problems and a bar relative to the first
+// one, spaced to fit `limit` rows.
+pub fn breakdown(
+    layout: &Layout,
+    id: &str,
+    title: &str,
+    column: usize,
+    limit: usize,
+    rows: &[(&str, u32, &str)],
+    // Metrics of the names and of the counts.
+    (body, number): (&Metrics, &Metrics),
+) -> Item {
+    let (x, width) = layout.column(column);
+    let (title_y, first_y, spacing) = layout.column_rows(limit);
+    let max = rows
+        .iter()
+        .map(|(_, solved, _)| *solved)
+        .max()
+        .unwrap_or_default();
 
-    let childs = [
-        (Difficulty::Easy, "var(--color-1)"),
-        (Difficulty::Medium, "var(--color-2)"),
-        (Difficulty::Hard, "var(--color-3)"),
-    ]
-    .iter()
-    .enumerate()
-    .map(|(i, (d, c))| {
-        let problem = problems.iter().find(|p| p.difficulty == *d).unwrap();
-        let pb_type = Item::new(
-            "text",
-            Some(attribute!({
-                "id": format!("{}-solved-type", d),
-            })),
-            Some(style! {
-                "fill": "var(--text-1)",
-                "font-size": "18px",
-                "font-weight": "bold"
-            }),
-            None,
-            None,
-            Some(d.capitalize()),
-        );
-        let count = Item::new(
-            "text",
-            Some(attribute!({
-                "id": format!("{}-solved-count", d),
-            })),
-            Some(style! {
-                "transform": "translate(300px, 0px)",
-                "fill": "var(--text-1)",
-                "font-size": "16px",
-                "font-weight": "bold",
-                "text-anchor": "end"
-            }),
-            None,
-            None,
-            Some(format!("{} / {}", problem.count, problem.total)),
-        );
-        let line_bg = Item::new(
-            "line",
-            Some(attribute!({
-                "id": format!("{}-solved-bg", d),
-                "x1": "0",
-                "y1": "10",
-                "x2": "300",
-                "y2": "10",
-            })),
-            Some(style! {
-                "stroke": "var(--bg-1)",
-                "stroke-width": "4px",
-                "stroke-linecap": "round"
-            }),
-            None,
-            None,
-            None,
-        );
-        let line_progress = Item::new(
-            "line",
-            Some(attribute!({
-                "id": format!("{}-solved-progress", d),
-                "x1": "0",
-                "y1": "10",
-                "x2": "300",
-                "y2": "10",
-            })),
-            Some(style! {
-                "stroke": c,
-                "stroke-width": "4px",
-                "stroke-dasharray": format!("{} 10000", 300.0 * problem.count as f64 / problem.total as f64),
-                "stroke-linecap": "round"
-            }),
-            None,
-            None,
-            None,
+    let title = Item::text(title)
+        .id(&format!("{id}-title"))
+        .translate(0.0, title_y)
+        .fill("var(--text-0)")
+        .font(16, true)
+        .font_family(font::HEADING);
+    let mut childs = rows
+        .iter()
+        .enumerate()
+        .map(|(i, (name, solved, color))| {
+            // Long names are shrunk, then truncated, to stay clear of the count.
+            let count = solved.to_string();
+            let max_width = width - number.width(&count, 14.0) - BREAKDOWN_GAP;
+            let (name, size) = body.fit(name, 14.0, 10.0, max_width);
+            let name = Item::text(name)
+                .id(&format!("{id}-{i}-name"))
+                .fill("var(--text-1)")
+                .font(size as u32, true);
+            let count = Item::text(count)
+                .id(&format!("{id}-{i}-count"))
+                .translate(width, 0.0)
+                .fill("var(--text-1)")
+                .font(14, true)
+                .font_family(font::NUMBER)
+                .anchor("end");
+            let line_bg = Item::line(0.0, 8.0, width, 8.0)
+                .id(&format!("{id}-{i}-bg"))
+                .stroke("var(--bg-1)", 4.0)
+                .style(style! {
+                    "stroke-linecap": "round"
+                });
+            let line_progress = Item::line(0.0, 8.0, width, 8.0)
+                .id(&format!("{id}-{i}-progress"))
+                .stroke(color, 4.0)
+                .style(style! {
+                    "stroke-dasharray": format!("{} 10000", width * *solved as f64 / max.max(1) as f64),
+                    "stroke-linecap": "round"
+                });
+
+            Item::g()
+                .id(&format!("{id}-{i}"))
+                .translate(0.0, first_y + spacing * i as f64)
+                .children([name, count, line_bg, line_progress])
+        })
+        .collect::<Vec<Item>>();
+    if rows.is_empty() {
+        childs.push(
+            Item::text("Nothing solved yet")
+                .id(&format!("{id}-empty"))
+                .translate(0.0, first_y)
+                .fill("var(--text-1)")
+                .font(14, false),
         );
+    }
 
-        Item::new(
-            "g",
-            Some(attribute!({
-                "id": format!("{}-solved", d),
-            })),
-            Some(style! {
-                "transform": format!("translate(0px, {}px)", 40 * i),
-            }),
-            None,
-            Some(vec![pb_type, count, line_bg, line_progress]),
-            None,
-        )
+    Item::g()
+        .id(id)
+        .translate(x, 0.0)
+        .child(title)
+        .children(childs)
+}
+
+// Last accepted submissions listed below the card at `y`, each one linking
+// to its problem with the color of its difficulty and how long ago it was.
+pub fn recent_submissions(
+    layout: &Layout,
+    y: f64,
+    submissions: &[RecentSubmission],
+    now: i64,
+    metrics: &Metrics,
+) -> Item {
+    let (title_y, first_y, spacing) = layout.list(y);
+    let (x, right) = (layout.title_x() - 35.0, layout.ranking_x());
+    let width = right - x;
+
+    let title = Item::text("Recent submissions")
+        .id("recent-title")
+        .translate(0.0, title_y)
+        .fill("var(--text-0)")
+        .font(16, true)
+        .font_family(font::HEADING);
+    let rows = submissions.iter().enumerate().map(|(i, submission)| {
+        let color = match submission.difficulty {
+            Some(Difficulty::Easy) => "var(--color-1)",
+            Some(Difficulty::Medium) => "var(--color-2)",
+            Some(Difficulty::Hard) => "var(--color-3)",
+            _ => "var(--text-1)",
+        };
+        let time = calendar::relative(submission.timestamp, now);
+        let max_width = width - 14.0 - metrics.width(&time, 12.0) - 12.0;
+        let (text, size) = metrics.fit(&submission.title, 14.0, 14.0, max_width);
+
+        let dot = Item::circle(4.0, -4.5, 4.0)
+            .id(&format!("recent-{i}-difficulty"))
+            .fill(color);
+        let title = Item::text(text)
+            .id(&format!("recent-{i}-title"))
+            .translate(14.0, 0.0)
+            .fill("var(--text-0)")
+            .font(size as u32, false);
+        let time = Item::text(time)
+            .id(&format!("recent-{i}-time"))
+            .translate(width, 0.0)
+            .fill("var(--text-1)")
+            .font(12, false)
+            .anchor("end");
+
+        Item::a(&format!(
+            "https://leetcode.com/problems/{}/",
+            submission.title_slug
+        ))
+        .id(&format!("recent-{i}"))
+        .translate(0.0, first_y + spacing * i as f64)
+        .children([dot, title, time])
+    });
+
+    Item::g()
+        .id("recent")
+        .translate(x, 0.0)
+        .child(title)
+        .children(rows)
+}
+
+// Row of earned badges below the card at `y`, each one with its inlined icon
+// or, when it could not be fetched, an empty disc. `total` counts the badges
+// left out of the row too.
+pub fn badges(layout: &Layout, y: f64, badges: &[(&Badge, Option<&str>)], total: usize) -> Item {
+    let (title_y, icons_y, size, pitch) = layout.badges(y);
+    let x = layout.title_x() - 35.0;
+    let width = layout.ranking_x() - x;
+
+    let title = Item::text("Badges")
+        .id("badges-title")
+        .translate(0.0, title_y)
+        .fill("var(--text-0)")
+        .font(16, true)
+        .font_family(font::HEADING);
+    let count = Item::text(match total {
+        1 => "1 badge".to_string(),
+        total => format!("{total} badges"),
     })
-    .collect::<Vec<Item>>();
+    .id("badges-count")
+    .translate(width, title_y)
+    .fill("var(--text-1)")
+    .font(12, false)
+    .anchor("end");
+    let mut childs = vec![title, count];
+
+    childs.extend(badges.iter().enumerate().map(|(i, (badge, icon))| {
+        let icon = match icon {
+            Some(icon) => Item::image(0.0, 0.0, size, size, icon),
+            None => Item::circle(size / 2.0, size / 2.0, size / 2.0 - 2.0).fill("var(--bg-1)"),
+        };
+        Item::g()
+            .id(&format!("badge-{i}"))
+            .translate(pitch * i as f64, icons_y)
+            .child(Item::title(format!(
+                "{}, earned on {}",
+                badge.name, badge.earned
+            )))
+            .child(icon)
+    }));
+    if badges.is_empty() {
+        childs.push(
+            Item::text("No badges earned yet")
+                .id("badges-empty")
+                .translate(0.0, icons_y + size / 2.0 + 5.0)
+                .fill("var(--text-1)")
+                .font(14, false),
+        );
+    }
+
+    Item::g().id("badges").translate(x, 0.0).children(childs)
+}
+
+const BADGE_PADDING: f64 = 8.0;
+
+// Shield-style badge drawing `label` next to the icon and `value` on a
+// section of `color` at the right end. The label is shrunk, then truncated,
+// to fit in the space left by the value.
+pub fn badge(
+    layout: &Layout,
+    label: &str,
+    value: &str,
+    color: &str,
+    heading: &Metrics,
+    number: &Metrics,
+) -> Item {
+    let (width, height) = (layout.width() as f64, layout.height() as f64);
+    let size = (height / 2.0).min(14.0).floor();
+    let (icon_x, _, icon_scale) = layout.icon();
+
+    let value_width = number.width(value, size) + 2.0 * BADGE_PADDING;
+    let label_x = icon_x + 94.0 * icon_scale + 6.0;
+    let split = (width - value_width).round().max(label_x + BADGE_PADDING);
+    let (text, label_size) = heading.fit(label, size, 10.0, split - BADGE_PADDING - label_x);
+
+    let central = || {
+        style! {
+            "alignment-baseline": "central",
+            "dominant-baseline": "central"
+        }
+    };
+    let section = Item::path(&format!(
+        "M{split},0.5H{}a4,4 0 0 1 4,4V{}a4,4 0 0 1 -4,4H{split}Z",
+        width - 4.5,
+        height - 4.5
+    ))
+    .id("badge-value-bg")
+    .fill(color);
+    let label = Item::text(text)
+        .id("badge-label")
+        .translate(label_x, height / 2.0)
+        .fill("var(--text-0)")
+        .font(label_size as u32, true)
+        .font_family(font::HEADING)
+        .style(central());
+    let value = Item::text(value)
+        .id("badge-value")
+        .translate((split + width) / 2.0, height / 2.0)
+        .fill("var(--bg-0)")
+        .font(size as u32, true)
+        .font_family(font::NUMBER)
+        .anchor("middle")
+        .style(central());
+
+    Item::g().id("badge").children([section, label, value])
+}
+
+pub fn error_title(layout: &Layout, title: &str) -> Item {
+    Item::text(title)
+        .id("error-title")
+        .translate(layout.title_x(), layout.header_y())
+        .fill("var(--text-0)")
+        .font(24, true)
+        .font_family(font::HEADING)
+}
+
+pub fn error_status(layout: &Layout, status: u16) -> Item {
+    Item::text(format!("{status}"))
+        .id("error-status")
+        .translate(layout.ranking_x(), layout.header_y())
+        .fill("var(--color-3)")
+        .font(18, true)
+        .font_family(font::NUMBER)
+        .anchor("end")
+}
+
+// The hint may list every accepted value, it is shrunk, then truncated, to
+// keep the margins of the card.
+pub fn error_hint(layout: &Layout, hint: &str, body: &Metrics) -> Item {
+    let width = layout.width() as f64;
+    let max_width = width - 2.0 * (width - layout.ranking_x());
+    let (text, size) = body.fit(hint, 16.0, 10.0, max_width);
 
-    Item::new("g", Some(attr), Some(style), None, Some(childs), None)
+    Item::text(text)
+        .id("error-hint")
+        .translate(width / 2.0, (layout.height() as f64 + 55.0) / 2.0)
+        .fill("var(--text-1)")
+        .font(size as u32, false)
+        .anchor("middle")
+        .style(style! {
+            "dominant-baseline": "central"
+        })
 }
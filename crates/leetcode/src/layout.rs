@@ -0,0 +1,458 @@
+use core::error::{Error, Result};
+
+use crate::calendar::WEEKS;
+
+const PADDING: f64 = 20.0;
+const HEADER_HEIGHT: f64 = 60.0;
+const RING_RADIUS: f64 = 40.0;
+const RING_GAP: f64 = 50.0;
+const ROW_SPACING: f64 = 40.0;
+const STATS_WIDTH: f64 = 180.0;
+const STATS_GAP: f64 = 40.0;
+const MONTH_LABEL: f64 = 14.0;
+const LIST_TITLE: f64 = 14.0;
+const LIST_ROW: f64 = 24.0;
+const BADGE_SIZE: f64 = 40.0;
+const BADGE_GAP: f64 = 10.0;
+// Rows of the skills columns fitting in the smallest card, and the height
+// added by each further one.
+const SKILL_ROWS: usize = 3;
+const SKILL_ROW_HEIGHT: u32 = 30;
+// Largest width and height, a raster card allocates 4 bytes per pixel.
+const MAX_SIZE: u32 = 2000;
+
+// Composition of the card: the full card, a single line badge, the full card
+// with a column of extra stats, the submission heatmap on its own, the
+// contest rating and its history or the solved problems per language and
+// topic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Kind {
+    #[default]
+    Default,
+    Compact,
+    Wide,
+    Heatmap,
+    Contest,
+    Skills,
+}
+
+impl Kind {
+    // Size of the card when none is configured.
+    pub fn size(&self) -> (u32, u32) {
+        match self {
+            Kind::Default => (500, 200),
+            Kind::Compact => (260, 28),
+            Kind::Wide => (800, 200),
+            Kind::Heatmap => (500, 150),
+            Kind::Contest => (500, 200),
+            Kind::Skills => (500, 200),
+        }
+    }
+
+    // Smallest card fitting the longest difficulty label and count next to
+    // the ring, or the badge texts at a readable size.
+    pub fn min_size(&self) -> (u32, u32) {
+        match self {
+            Kind::Default => (380, 170),
+            Kind::Compact => (160, 20),
+            Kind::Wide => (600, 170),
+            Kind::Heatmap => (320, 130),
+            Kind::Contest => (380, 170),
+            Kind::Skills => (380, 170),
+        }
+    }
+
+    // Height the card grows by to keep `rows` rows apart.
+    pub fn rows_height(&self, rows: usize) -> u32 {
+        match self {
+            Kind::Skills => SKILL_ROW_HEIGHT * rows.saturating_sub(SKILL_ROWS) as u32,
+            _ => 0,
+        }
+    }
+}
+
+impl TryFrom<&str> for Kind {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "default" => Ok(Kind::Default),
+            "compact" => Ok(Kind::Compact),
+            "wide" => Ok(Kind::Wide),
+            "heatmap" => Ok(Kind::Heatmap),
+            "contest" => Ok(Kind::Contest),
+            "skills" => Ok(Kind::Skills),
+            _ => Err(Error::new_invalid_parameter().with(format!(
+                "layout must be default, compact, wide, heatmap, contest or skills, got \"{value}\""
+            ))),
+        }
+    }
+}
+
+// Positions and sizes of the components, computed from the dimensions of the
+// card. Components are laid out for the default 500 by 200 card and follow
+// its right and bottom edges as it grows: the bars get longer and the rows
+// spread out, while the ring, the header and the text keep a fixed size.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Layout {
+    kind: Kind,
+    width: f64,
+    height: f64,
+    // Whether the heatmap is appended below the card.
+    heatmap: bool,
+    // Height appended below the card by extensions.
+    appended: f64,
+}
+
+impl Layout {
+    pub fn new(kind: Kind, width: u32, height: u32) -> Result<Layout> {
+        let (min_width, min_height) = kind.min_size();
+        check("width", width, min_width)?;
+        check("height", height, min_height)?;
+
+        Ok(Layout {
+            kind,
+            width: width as f64,
+            height: height as f64,
+            heatmap: false,
+            appended: 0.0,
+        })
+    }
+
+    // Appends the heatmap below the card, growing it by the height of the
+    // heatmap. A badge has no room for it.
+    pub fn with_heatmap(mut self, heatmap: bool) -> Layout {
+        self.heatmap = heatmap && matches!(self.kind, Kind::Default | Kind::Wide);
+        self
+    }
+
+    // Checks that the card is tall enough to keep `rows` rows apart.
+    pub fn with_rows(self, rows: usize) -> Result<Layout> {
+        let min_height = self.kind.min_size().1 + self.kind.rows_height(rows);
+        check("height", self.height as u32, min_height)?;
+        Ok(self)
+    }
+
+    // Grows the card by `height` pixels, for sections appended by extensions.
+    pub fn with_appended(mut self, height: f64) -> Layout {
+        self.appended += height;
+        self
+    }
+
+    pub fn kind(&self) -> Kind {
+        self.kind
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width as u32
+    }
+
+    pub fn height(&self) -> u32 {
+        (self.height + self.appended_height() + self.appended).round() as u32
+    }
+
+    // Top left corner and scale of the icon, sized to the badge height when
+    // compact.
+    pub fn icon(&self) -> (f64, f64, f64) {
+        match self.kind {
+            Kind::Compact => (6.0, 4.0, (self.height - 8.0) / 110.0),
+            _ => (20.0, 15.0, 0.27),
+        }
+    }
+
+    pub fn header_y(&self) -> f64 {
+        40.0
+    }
+
+    pub fn title_x(&self) -> f64 {
+        65.0
+    }
+
+    // Right edge of the ranking, or of the status of an error.
+    pub fn ranking_x(&self) -> f64 {
+        self.width - PADDING
+    }
+
+    pub fn ring_radius(&self) -> f64 {
+        RING_RADIUS
+    }
+
+    // Top left corner of the box around the ring, centered in the body.
+    pub fn ring(&self) -> (f64, f64) {
+        (PADDING + 10.0, self.body_center() - RING_RADIUS)
+    }
+
+    // Top left corner of the difficulty rows.
+    pub fn rows(&self) -> (f64, f64) {
+        let x = PADDING + 10.0 + 2.0 * RING_RADIUS + RING_GAP;
+        (x, self.body_center() - ROW_SPACING - 5.0)
+    }
+
+    pub fn row_spacing(&self) -> f64 {
+        ROW_SPACING
+    }
+
+    pub fn bar_length(&self) -> f64 {
+        match self.kind {
+            Kind::Wide => self.stats().0 - STATS_GAP - self.rows().0,
+            _ => self.width - 2.0 * PADDING - self.rows().0,
+        }
+    }
+
+    // Top left corner of the column of extra stats, aligned on the rows.
+    pub fn stats(&self) -> (f64, f64) {
+        (self.width - PADDING - STATS_WIDTH, self.rows().1)
+    }
+
+    pub fn stats_width(&self) -> f64 {
+        STATS_WIDTH
+    }
+
+    // Top left corner and size of the rating chart, right of the summary and
+    // above its date labels.
+    pub fn chart(&self) -> (f64, f64, f64, f64) {
+        let (x, _) = self.rows();
+        let y = HEADER_HEIGHT + 5.0;
+        (
+            x,
+            y,
+            self.width - PADDING - x,
+            self.height - y - PADDING - 10.0,
+        )
+    }
+
+    // Left edge and width of one of the two columns of the skills card.
+    pub fn column(&self, column: usize) -> (f64, f64) {
+        let width = self.width / 2.0 - PADDING - 20.0;
+        (PADDING + 10.0 + column as f64 * self.width / 2.0, width)
+    }
+
+    // Baseline of the column titles, then of the first row and the spacing
+    // between `rows` rows filling the body below.
+    pub fn column_rows(&self, rows: usize) -> (f64, f64, f64) {
+        let title = HEADER_HEIGHT + 20.0;
+        let spacing = ((self.height - title - PADDING - 10.0) / rows.max(1) as f64).floor();
+        (title, title + spacing, spacing)
+    }
+
+    // Baseline of the title of a list appended at `y` and of its first row,
+    // and the spacing between its rows.
+    pub fn list(&self, y: f64) -> (f64, f64, f64) {
+        let title = y + LIST_TITLE;
+        (title, title + LIST_ROW + 4.0, LIST_ROW)
+    }
+
+    // Height of a list of `rows` rows, below its title.
+    pub fn list_height(&self, rows: usize) -> f64 {
+        LIST_TITLE + 4.0 + LIST_ROW * rows as f64 + PADDING
+    }
+
+    // Baseline of the title of a badge row appended at `y`, top of its icons,
+    // their size and the distance between two of them.
+    pub fn badges(&self, y: f64) -> (f64, f64, f64, f64) {
+        let title = y + LIST_TITLE;
+        (title, title + 12.0, BADGE_SIZE, BADGE_SIZE + BADGE_GAP)
+    }
+
+    // Icons fitting on a badge row, between the margins of the lists.
+    pub fn badges_per_row(&self) -> usize {
+        let width = self.width - 2.0 * (PADDING + 10.0);
+        ((width + BADGE_GAP) / (BADGE_SIZE + BADGE_GAP)).floor() as usize
+    }
+
+    pub fn badges_height(&self) -> f64 {
+        LIST_TITLE + 12.0 + BADGE_SIZE + PADDING
+    }
+
+    pub fn has_heatmap(&self) -> bool {
+        self.heatmap || self.kind == Kind::Heatmap
+    }
+
+    // Top left corner of the heatmap grid, centered, and the distance between
+    // two of its days.
+    pub fn heatmap(&self) -> (f64, f64, f64) {
+        let pitch = self.heatmap_pitch();
+        let x = ((self.width - WEEKS as f64 * pitch) / 2.0).round();
+        let y = match self.kind {
+            Kind::Heatmap => HEADER_HEIGHT + MONTH_LABEL,
+            _ => self.height - 10.0 + MONTH_LABEL,
+        };
+        (x, y, pitch)
+    }
+
+    fn heatmap_pitch(&self) -> f64 {
+        let pitch = (self.width - 2.0 * PADDING) / WEEKS as f64;
+        let pitch = match self.kind {
+            Kind::Heatmap => pitch.min((self.height - HEADER_HEIGHT - MONTH_LABEL - PADDING) / 7.0),
+            _ => pitch,
+        };
+        (pitch * 100.0).floor() / 100.0
+    }
+
+    // Height added below the card by an appended heatmap.
+    fn appended_height(&self) -> f64 {
+        match self.heatmap {
+            true => MONTH_LABEL + 7.0 * self.heatmap_pitch() + 10.0,
+            false => 0.0,
+        }
+    }
+
+    // Center of the area under the header.
+    pub fn body_center(&self) -> f64 {
+        (HEADER_HEIGHT + self.height - 10.0) / 2.0
+    }
+}
+
+impl From<Kind> for Layout {
+    fn from(kind: Kind) -> Self {
+        let (width, height) = kind.size();
+        Layout {
+            kind,
+            width: width as f64,
+            height: height as f64,
+            heatmap: false,
+            appended: 0.0,
+        }
+    }
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Kind::Default.into()
+    }
+}
+
+fn check(name: &str, value: u32, min: u32) -> Result<()> {
+    if value < min {
+        return Err(Error::new_invalid_parameter()
+            .with(format!("{name} must be at least {min} pixels, got {value}")));
+    }
+    if value > MAX_SIZE {
+        return Err(Error::new_invalid_parameter().with(format!(
+            "{name} must be at most {MAX_SIZE} pixels, got {value}"
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_layout_matches_the_original_card() {
+        let layout = Layout::new(Kind::Default, 500, 200).unwrap();
+
+        assert_eq!(layout, Layout::default());
+        assert_eq!(layout.ranking_x(), 480.0);
+        assert_eq!(layout.ring(), (30.0, 85.0));
+        assert_eq!(layout.rows(), (160.0, 80.0));
+        assert_eq!(layout.bar_length(), 300.0);
+    }
+
+    #[test]
+    fn dimensions_are_bounded() {
+        assert!(Layout::new(Kind::Wide, MAX_SIZE, MAX_SIZE).is_ok());
+        let error = Layout::new(Kind::Default, 20_000, 200).unwrap_err();
+        assert_eq!(error.hint(), "width must be at most 2000 pixels, got 20000");
+        assert!(Layout::new(Kind::Default, 500, MAX_SIZE + 1).is_err());
+    }
+
+    #[test]
+    fn components_follow_the_dimensions() {
+        let layout = Layout::new(Kind::Default, 800, 300).unwrap();
+
+        assert_eq!(layout.ranking_x(), 780.0);
+        assert_eq!(layout.bar_length(), 600.0);
+        assert_eq!(layout.ring(), (30.0, 135.0));
+    }
+
+    #[test]
+    fn small_cards_are_rejected() {
+        let (width, height) = Kind::Default.min_size();
+        assert!(Layout::new(Kind::Default, width, height).is_ok());
+
+        let error = Layout::new(Kind::Default, 300, 200).unwrap_err();
+        assert_eq!(error.hint(), "width must be at least 380 pixels, got 300");
+        assert!(Layout::new(Kind::Default, 500, 100).is_err());
+        assert!(Layout::new(Kind::Compact, 300, 28).is_ok());
+        assert!(Layout::new(Kind::Wide, 500, 200).is_err());
+    }
+
+    #[test]
+    fn wide_bars_leave_room_for_the_stats() {
+        let layout = Layout::from(Kind::Wide);
+
+        assert_eq!(layout.stats(), (600.0, 80.0));
+        assert_eq!(layout.bar_length(), 400.0);
+        assert!(layout.rows().0 + layout.bar_length() < layout.stats().0);
+    }
+
+    #[test]
+    fn chart_fills_the_body() {
+        assert_eq!(
+            Layout::from(Kind::Contest).chart(),
+            (160.0, 65.0, 320.0, 105.0)
+        );
+        assert_eq!(
+            Layout::new(Kind::Contest, 600, 300).unwrap().chart(),
+            (160.0, 65.0, 420.0, 205.0)
+        );
+    }
+
+    #[test]
+    fn skills_rows_fill_the_columns() {
+        let layout = Layout::from(Kind::Skills);
+
+        assert_eq!(layout.column(0), (30.0, 210.0));
+        assert_eq!(layout.column(1), (280.0, 210.0));
+        assert_eq!(layout.column_rows(3), (80.0, 110.0, 30.0));
+        assert_eq!(layout.column_rows(0), layout.column_rows(1));
+    }
+
+    #[test]
+    fn appended_lists_grow_the_card() {
+        let layout = Layout::default();
+        let grown = layout.with_appended(layout.list_height(5));
+
+        assert_eq!(layout.list(200.0), (214.0, 242.0, 24.0));
+        assert_eq!(grown.height(), 200 + 158);
+        assert_eq!(grown.body_center(), layout.body_center());
+    }
+
+    #[test]
+    fn badge_rows_fit_the_width() {
+        assert_eq!(Layout::default().badges_per_row(), 9);
+        assert_eq!(Layout::from(Kind::Wide).badges_per_row(), 15);
+        assert_eq!(Layout::default().badges(200.0), (214.0, 226.0, 40.0, 50.0));
+    }
+
+    #[test]
+    fn appended_heatmap_grows_the_card() {
+        let layout = Layout::default().with_heatmap(true);
+
+        assert!(layout.has_heatmap());
+        assert_eq!(layout.heatmap(), (20.0, 204.0, 8.67));
+        assert_eq!(layout.height(), 285);
+        assert_eq!(layout.body_center(), Layout::default().body_center());
+        assert!(!Layout::from(Kind::Compact).with_heatmap(true).has_heatmap());
+    }
+
+    #[test]
+    fn heatmap_fits_its_own_card() {
+        let layout = Layout::from(Kind::Heatmap);
+        let (x, y, pitch) = layout.heatmap();
+
+        assert_eq!(layout.height(), 150);
+        assert!(x >= PADDING && x + WEEKS as f64 * pitch <= 500.0 - PADDING);
+        assert!(y + 7.0 * pitch <= 150.0 - PADDING);
+    }
+
+    #[test]
+    fn layouts_are_parsed_case_insensitively() {
+        assert_eq!(Kind::try_from("Compact").unwrap(), Kind::Compact);
+        assert_eq!(Kind::try_from("wide").unwrap(), Kind::Wide);
+        assert_eq!(Kind::try_from("CONTEST").unwrap(), Kind::Contest);
+        assert!(Kind::try_from("badge").is_err());
+    }
+}
@@ -1,36 +1,51 @@
 #![allow(dead_code)]
 
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
 use core::{
-    error::Result,
-    font::Font,
-    item::{Item, ItemBuilder},
-    theme::Theme,
+    error::{Error, Result},
+    font::{FontStack, JsonFont},
+    item::{FontStyle, Item, ItemBuilder},
+    metrics::Metrics,
+    raster::Format,
+    theme::{ColorScheme, Theme, Variable},
     Extension, Generator as GeneratorTrait,
 };
+use layout::{Kind, Layout};
 
+mod calendar;
 pub mod extension;
 pub mod font;
 mod graphql;
+mod icon;
 mod item;
+pub mod layout;
+#[cfg(test)]
+mod tests;
 pub mod theme;
 
 pub struct Generator {
     config: Config,
     verbose: bool,
     user_info: Option<UserInfo>,
+    fonts: Vec<Vec<u8>>,
+    // Loaded fonts with the `font-family` values of the text they draw.
+    font_faces: Vec<(Arc<JsonFont>, Vec<Option<String>>)>,
+    recent: Option<RecentSubmissions>,
+    // Whether the row of badges was drawn below the card.
+    badges: bool,
+    // Themes whose gradients and patterns are defined with the final size of
+    // the card.
+    themes: Vec<Theme>,
+    // Height of the sections appended below the card by extensions.
+    appended: f64,
 }
 
 impl GeneratorTrait for Generator {
     async fn generate(mut self) -> Result<String> {
-        log! { self.verbose => "starting generation with config: {:?}", self.config };
-
-        log! { self.verbose => "awaiting user_info of: {:?}", self.config.username };
-        let user_id = graphql::Id::new(&self.config.username);
-        let client = graphql::Client::new(user_id).set_verbose(self.verbose);
-        let user_info = client.get().await.unwrap_or_default();
-        self.user_info = Some(user_info);
-        log! { self.verbose => "received user_info: {:?}", self.user_info };
-
+        self.config.layout()?;
+        self.fetch_user_info().await?;
         self.hydrate().await
     }
 }
@@ -41,47 +56,311 @@ impl Generator {
             config,
             verbose: false,
             user_info: None,
+            fonts: Vec::new(),
+            font_faces: Vec::new(),
+            recent: None,
+            badges: false,
+            themes: Vec::new(),
+            appended: 0.0,
         }
     }
 
-    async fn hydrate(mut self) -> Result<String> {
+    // Renders the card in the configured output format, rasterizing it when
+    // the format is not SVG.
+    pub async fn generate_bytes(mut self) -> Result<Vec<u8>> {
+        // Invalid dimensions are reported before reaching LeetCode.
+        self.config.layout()?;
+        self.fetch_user_info().await?;
+        let svg = self.hydrate().await?;
+
+        log! {self.verbose => "rendering card as {:?}", self.config.format};
+        core::raster::render(&svg, &self.fonts, self.config.format)
+    }
+
+    // Renders a card describing `error` with the configured themes, font and
+    // output format, so that a failure still shows up as an image.
+    pub async fn generate_error(mut self, error: &Error) -> Result<Vec<u8>> {
+        log! {self.verbose => "generating error card for: {:?}", error};
+        let mut ext_style = Vec::new();
+        let mut ext_body = Vec::new();
+
+        for ext in self.config.get_error_extensions() {
+            // The font is fetched over the network, which may well be what
+            // failed in the first place, the card then uses the default font.
+            if let Err(e) = ext.extend(&mut self, &mut ext_body, &mut ext_style).await {
+                log! {self.verbose => "skipping extension of the error card: {:?}", e};
+            }
+        }
+
+        let svg = self.render_error(error, ext_body, &ext_style);
+        core::raster::render(&svg, &self.fonts, self.config.format)
+    }
+
+    async fn fetch_user_info(&mut self) -> Result<()> {
+        log! { self.verbose => "starting generation with config: {:?}", self.config };
+
+        log! { self.verbose => "awaiting user_info of: {:?}", self.config.username };
+        let user_id = graphql::Id::new(&self.config.username);
+        let client = graphql::Client::new(user_id).set_verbose(self.verbose);
+        let user_info = client.get(self.config.sections()?).await?;
+        self.user_info = Some(user_info);
+        log! { self.verbose => "received user_info: {:?}", self.user_info };
+
+        Ok(())
+    }
+
+    async fn hydrate(&mut self) -> Result<String> {
+        let extensions = self.config.get_extensions();
+        self.hydrate_with(extensions).await
+    }
+
+    async fn hydrate_with(
+        &mut self,
+        extensions: impl IntoIterator<Item = extension::Extension>,
+    ) -> Result<String> {
         log! {self.verbose => "starting hydration..."};
         let mut ext_style = Vec::new();
         let mut ext_body = Vec::new();
+        self.appended = 0.0;
+        self.themes.clear();
 
         log! {self.verbose => "starting extending extensions"};
-        for ext in self.config.get_extensions() {
-            ext.extend(&mut self, &mut ext_body, &mut ext_style).await?;
+        for ext in extensions {
+            ext.extend(self, &mut ext_body, &mut ext_style).await?;
         }
         log! {self.verbose => "ending extending extensions"};
 
+        let layout = self.config.layout()?.with_appended(self.appended);
+
+        let svg = self.render(&layout, ext_body, &ext_style);
+        log! {self.verbose => "ending hydration..."};
+
+        Ok(svg)
+    }
+
+    fn render(&self, layout: &Layout, ext_body: Vec<Item>, ext_style: &[String]) -> String {
         log! {self.verbose => "starting building DOM"};
 
         let user_info = self.get_user_info();
-        let mut root = item::root(self.config.width, self.config.height, user_info);
+        let title = format!("{} | LeetCode Stat Card", user_info.username);
+        let mut root = item::root(layout, &title);
+
+        root.push_child(item::icon(layout));
+        let mut components = match layout.kind() {
+            Kind::Default => self.card(layout, user_info),
+            Kind::Compact => vec![self.compact_card(layout, user_info)],
+            Kind::Wide => {
+                let mut components = self.card(layout, user_info);
+                components.push(self.stats(layout, user_info));
+                components
+            }
+            Kind::Heatmap => self.header(layout, user_info),
+            Kind::Skills => {
+                let mut components = self.header(layout, user_info);
+                components.extend(self.skills(layout, user_info));
+                components
+            }
+            Kind::Contest => {
+                let mut components = self.header(layout, user_info);
+                match &user_info.contest {
+                    Some(contest) => {
+                        components.push(item::contest(layout, contest));
+                        components.push(item::rating_chart(layout, &contest.history));
+                    }
+                    None => components.push(item::no_contest(layout)),
+                }
+                components
+            }
+        };
+        if layout.has_heatmap() {
+            components.push(item::heatmap(
+                layout,
+                &user_info.calendar,
+                &self.metrics(font::BODY, FontStyle::default()),
+            ));
+        }
+        components
+            .into_iter()
+            .for_each(|component| root.push_child(component));
+
+        let svg = self.finish(layout, root, ext_body, ext_style);
+        log! {self.verbose => "ending building DOM"};
+
+        svg
+    }
+
+    fn header(&self, layout: &Layout, user_info: &UserInfo) -> Vec<Item> {
+        let heading = self.metrics(font::HEADING, item::BOLD);
+        let number = self.metrics(font::NUMBER, item::BOLD);
+
+        vec![
+            item::username(
+                layout,
+                &user_info.username,
+                user_info.profile.ranking,
+                &heading,
+                &number,
+            ),
+            item::ranking(layout, user_info.profile.ranking),
+        ]
+    }
+
+    fn card(&self, layout: &Layout, user_info: &UserInfo) -> Vec<Item> {
         let (solved, total) = user_info.problems_stats();
 
-        root.push_child(item::icon());
-        root.push_child(item::username(&user_info.username));
-        root.push_child(item::ranking(user_info.profile.ranking));
-        root.push_child(item::total_solved(solved, total));
-        root.push_child(item::solved(&user_info.submissions));
+        let mut components = self.header(layout, user_info);
+        components.push(item::total_solved(layout, solved, total));
+        components.push(item::solved(layout, &user_info.submissions));
+        components
+    }
+
+    fn compact_card(&self, layout: &Layout, user_info: &UserInfo) -> Item {
+        let (solved, _) = user_info.problems_stats();
+        let badge = item::badge(
+            layout,
+            &user_info.username,
+            &format!("{solved} solved"),
+            "var(--color-0)",
+            &self.metrics(font::HEADING, item::BOLD),
+            &self.metrics(font::NUMBER, item::BOLD),
+        );
+
+        Item::a(&format!("https://leetcode.com/{}/", user_info.username))
+            .id("username")
+            .child(badge)
+    }
+
+    fn stats(&self, layout: &Layout, user_info: &UserInfo) -> Item {
+        let submissions = user_info
+            .submissions
+            .iter()
+            .find(|p| p.difficulty == Difficulty::All)
+            .map_or(0, |p| p.submissions);
+
+        let mut stats = vec![
+            ("Streak", format!("{} days", user_info.streak)),
+            ("Submissions", submissions.to_string()),
+        ];
+        if let Some(country) = user_info.profile.country.as_ref().filter(|c| !c.is_empty()) {
+            stats.push(("Country", country.clone()));
+        }
+
+        item::stats(layout, &stats)
+    }
+
+    fn skills(&self, layout: &Layout, user_info: &UserInfo) -> [Item; 2] {
+        let limit = self.config.limit;
+        let languages = user_info
+            .languages
+            .iter()
+            .take(limit)
+            .map(|language| (language.name.as_str(), language.solved, "var(--color-0)"))
+            .collect::<Vec<(&str, u32, &str)>>();
+        let tags = user_info
+            .tags
+            .iter()
+            .take(limit)
+            .map(|tag| (tag.name.as_str(), tag.solved, tag.level.color()))
+            .collect::<Vec<(&str, u32, &str)>>();
+
+        let body = self.metrics(font::BODY, item::BOLD);
+        let number = self.metrics(font::NUMBER, item::BOLD);
+        let metrics = (&body, &number);
+        [
+            item::breakdown(
+                layout,
+                "languages",
+                "Languages",
+                0,
+                limit,
+                &languages,
+                metrics,
+            ),
+            item::breakdown(layout, "tags", "Topics", 1, limit, &tags, metrics),
+        ]
+    }
 
+    fn render_error(&self, error: &Error, ext_body: Vec<Item>, ext_style: &[String]) -> String {
+        // The error may be about the dimensions themselves.
+        let layout = self
+            .config
+            .layout()
+            .map(|layout| layout.with_heatmap(false))
+            .unwrap_or_else(|_| self.config.layout.into());
+        let title = error.kind().title();
+        let mut root = item::root(&layout, &format!("{} | LeetCode Stat Card", title));
+
+        root.push_child(item::icon(&layout));
+        if layout.kind() == Kind::Compact {
+            root.push_child(item::badge(
+                &layout,
+                title,
+                &error.status().to_string(),
+                "var(--color-3)",
+                &self.metrics(font::HEADING, item::BOLD),
+                &self.metrics(font::NUMBER, item::BOLD),
+            ));
+        } else {
+            root.push_child(item::error_title(&layout, title));
+            root.push_child(item::error_status(&layout, error.status()));
+            root.push_child(item::error_hint(
+                &layout,
+                &error.hint(),
+                &self.metrics(font::BODY, FontStyle::default()),
+            ));
+        }
+
+        self.finish(&layout, root, ext_body, ext_style)
+    }
+
+    fn finish(
+        &self,
+        layout: &Layout,
+        mut root: Item,
+        ext_body: Vec<Item>,
+        ext_style: &[String],
+    ) -> String {
         let mut builder = ItemBuilder::default();
+        let (width, height) = (layout.width() as f64, layout.height() as f64);
+        self.themes
+            .iter()
+            .map(|theme| theme.defs(width, height))
+            .filter(|defs| !defs.is_empty())
+            .for_each(|defs| root.push_child(Item::defs().children(defs)));
+        ext_body.into_iter().for_each(|item| root.push_child(item));
 
+        let runs = root.text_runs();
         let mut style = vec![
             "@namespace svg url(http://www.w3.org/2000/svg);".to_string(),
             builder.css(&mut root),
         ];
-        style.extend_from_slice(&ext_style);
+        for (font, families) in &self.font_faces {
+            let mut drawn = BTreeMap::<FontStyle, String>::new();
+            for ((family, font_style), text) in &runs {
+                if families.contains(family) {
+                    drawn.entry(*font_style).or_default().push_str(text);
+                }
+            }
+            style.extend(font.font_faces(&drawn));
+        }
+        style.extend_from_slice(ext_style);
         style.push("svg{opacity:1}".to_string());
 
-        root.push_child(Item::style(style.join("")));
+        root.push_child(Item::stylesheet(style.join("")));
 
-        log! {self.verbose => "ending building DOM"};
-        log! {self.verbose => "ending hydration..."};
+        builder.stringify(&mut root)
+    }
 
-        Ok(builder.stringify(&mut root))
+    // Metrics of the first font of the stack drawing `family`, estimated when
+    // it is a system font.
+    fn metrics(&self, family: &str, style: FontStyle) -> Metrics {
+        let font = self.config.fonts.stack(Some(family)).fonts().next();
+        self.font_faces
+            .iter()
+            .find(|(json, _)| font.is_some_and(|font| json.name == font.font_family()))
+            .and_then(|(json, _)| json.face(style)?.ttf_bytes())
+            .and_then(|ttf| Metrics::new(ttf).ok())
+            .unwrap_or_else(Metrics::approximate)
     }
 
     pub fn verbose(&mut self) {
@@ -101,14 +380,23 @@ impl Generator {
     }
 }
 
+// Most languages and topics listed by the skills card.
+pub const MAX_LIMIT: usize = 10;
+
 #[derive(Clone, Debug)]
 pub struct Config {
     username: String,
-    width: u32,
-    height: u32,
+    width: Option<u32>,
+    height: Option<u32>,
+    layout: Kind,
+    heatmap: bool,
+    limit: usize,
     themes: [Option<Theme>; 2],
-    font: Font,
+    colors: Vec<Variable>,
+    fonts: font::Fonts,
     animation: bool,
+    format: Format,
+    color_scheme: ColorScheme,
     extensions: Vec<extension::Extension>,
 }
 
@@ -121,15 +409,62 @@ impl Config {
     }
 
     pub fn set_width(mut self, width: u32) -> Self {
-        self.width = width;
+        self.width = Some(width);
         self
     }
 
     pub fn set_height(mut self, height: u32) -> Self {
-        self.height = height;
+        self.height = Some(height);
+        self
+    }
+
+    // Unset dimensions default to the size of the layout.
+    pub fn set_layout(mut self, layout: Kind) -> Self {
+        self.layout = layout;
         self
     }
 
+    // Appends the submission heatmap below the card.
+    pub fn set_heatmap(mut self, heatmap: bool) -> Self {
+        self.heatmap = heatmap;
+        self
+    }
+
+    // Languages and topics listed by the skills card, at most `MAX_LIMIT`.
+    pub fn set_limit(mut self, limit: usize) -> Self {
+        self.limit = limit.min(MAX_LIMIT);
+        self
+    }
+
+    pub fn layout(&self) -> Result<Layout> {
+        let (width, height) = self.layout.size();
+        // The skills card grows to keep its rows apart.
+        let layout = Layout::new(
+            self.layout,
+            self.width.unwrap_or(width),
+            self.height
+                .unwrap_or(height + self.layout.rows_height(self.limit)),
+        )?
+        .with_rows(self.limit)?;
+        Ok(layout.with_heatmap(self.heatmap))
+    }
+
+    // Parts of the profile drawn by the layout or the extensions, the others
+    // are not fetched.
+    fn sections(&self) -> Result<graphql::Sections> {
+        let layout = self.layout()?;
+        let badges = self
+            .extensions
+            .iter()
+            .any(|ext| matches!(ext, extension::Extension::Badges(_)));
+        Ok(graphql::Sections {
+            calendar: layout.has_heatmap(),
+            contest: layout.kind() == Kind::Contest,
+            skills: layout.kind() == Kind::Skills,
+            badges: badges && layout.kind() != Kind::Compact,
+        })
+    }
+
     pub fn set_username(mut self, username: &str) -> Self {
         self.username = username.to_string();
         self
@@ -139,8 +474,18 @@ impl Config {
         !self.username.is_empty()
     }
 
-    pub fn set_font(mut self, font: Font) -> Self {
-        self.font = font;
+    pub fn set_font(mut self, font: impl Into<FontStack>) -> Self {
+        self.fonts.body = font.into();
+        self
+    }
+
+    pub fn set_heading_font(mut self, font: impl Into<FontStack>) -> Self {
+        self.fonts.heading = Some(font.into());
+        self
+    }
+
+    pub fn set_number_font(mut self, font: impl Into<FontStack>) -> Self {
+        self.fonts.number = Some(font.into());
         self
     }
 
@@ -149,6 +494,22 @@ impl Config {
         self
     }
 
+    pub fn set_format(mut self, format: Format) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    // Raster formats cannot follow `prefers-color-scheme`, the theme matching
+    // this scheme is baked into the image instead.
+    pub fn set_color_scheme(mut self, color_scheme: ColorScheme) -> Self {
+        self.color_scheme = color_scheme;
+        self
+    }
+
     pub fn set_dark_theme(mut self, mut theme: Theme) -> Self {
         theme.set_dark();
         self.themes[1] = Some(theme);
@@ -166,6 +527,12 @@ impl Config {
         self
     }
 
+    // Colors merged onto every configured theme, later ones win.
+    pub fn add_colors(mut self, colors: Vec<Variable>) -> Self {
+        self.colors.extend(colors);
+        self
+    }
+
     pub fn add_extension(self, ext: extension::Extension) -> Self {
         let mut config = self;
         config.extensions.push(ext);
@@ -173,34 +540,114 @@ impl Config {
     }
 
     fn get_extensions(&self) -> Vec<extension::Extension> {
-        let mut extensions = self.extensions.clone();
-        extensions.extend(
-            self.themes
-                .clone()
-                .into_iter()
-                .flatten()
-                .map(|theme| theme.into()),
-        );
-        extensions.push(self.font.into());
-        if self.animation {
+        let mut extensions = self
+            .get_themes()
+            .into_iter()
+            .map(|theme| theme.into())
+            .collect::<Vec<extension::Extension>>();
+        extensions.push(self.get_fonts().into());
+        // Sections appended to the card are measured with the loaded fonts.
+        extensions.extend(self.extensions.clone());
+        // Animated elements start hidden, a still image would stay blank.
+        if self.animation && self.format == Format::Svg {
             extensions.push(extension::Extension::Animation);
         }
 
         extensions
     }
+
+    fn get_error_extensions(&self) -> Vec<extension::Extension> {
+        let mut extensions = self
+            .get_themes()
+            .into_iter()
+            .map(|theme| theme.into())
+            .collect::<Vec<extension::Extension>>();
+        extensions.push(self.get_fonts().into());
+
+        extensions
+    }
+
+    // A still image cannot use the fonts of the viewer's system, when none
+    // is embedded the text is drawn with the default font.
+    fn get_fonts(&self) -> font::Fonts {
+        let mut fonts = self.fonts.clone();
+        if self.format != Format::Svg && fonts.fonts().is_empty() {
+            fonts.body = fonts.body.with_fallback(core::font::DEFAULT);
+        }
+        fonts
+    }
+
+    fn get_themes(&self) -> Vec<Theme> {
+        let themes = self.merge_colors();
+        if self.format == Format::Svg {
+            return themes.into_iter().flatten().collect();
+        }
+
+        let [light, dark] = themes;
+        let theme = match self.color_scheme {
+            ColorScheme::Light => light,
+            ColorScheme::Dark => dark.or(light),
+        };
+        theme
+            .map(|mut theme| {
+                theme.set_no_preference();
+                theme
+            })
+            .into_iter()
+            .collect()
+    }
+
+    // Custom colors are merged onto both themes. When they move one of them to
+    // the other color scheme, e.g. a dark background over the light theme,
+    // that one is derived from the other theme instead.
+    fn merge_colors(&self) -> [Option<Theme>; 2] {
+        let [light, dark] = self
+            .themes
+            .clone()
+            .map(|theme| theme.map(|theme| theme.with_colors(&self.colors)));
+
+        match (light, dark) {
+            (Some(light), Some(dark)) if !self.colors.is_empty() => {
+                match (light.scheme(), dark.scheme()) {
+                    (ColorScheme::Dark, ColorScheme::Dark) => {
+                        let mut light = dark.counterpart();
+                        light.set_light();
+                        [Some(light), Some(dark)]
+                    }
+                    (ColorScheme::Light, ColorScheme::Light) => {
+                        let mut dark = light.counterpart();
+                        dark.set_dark();
+                        [Some(light), Some(dark)]
+                    }
+                    _ => [Some(light), Some(dark)],
+                }
+            }
+            (light, dark) => [light, dark],
+        }
+    }
 }
 
 impl Default for Config {
     fn default() -> Self {
-        Config {
-            width: 500,
-            height: 200,
+        let config = Config {
+            width: None,
+            height: None,
+            layout: Kind::Default,
+            heatmap: false,
+            limit: 3,
             animation: true,
-            username: String::from("thibaultcne"),
-            themes: [Some(core::theme::LIGHT), Some(core::theme::DARK)],
-            font: font::BALOO_2,
+            username: String::new(),
+            themes: [None, None],
+            colors: Vec::new(),
+            fonts: core::font::DEFAULT.into(),
+            format: Format::Svg,
+            color_scheme: ColorScheme::Light,
             extensions: Vec::new(),
-        }
+        };
+
+        config
+            .set_light_theme(Theme::from("light"))
+            .set_dark_theme(Theme::from("dark"))
     }
 }
 
@@ -210,6 +657,13 @@ struct UserInfo {
     profile: Profile,
     submissions: Vec<Problem>,
     streak: u32,
+    calendar: calendar::Calendar,
+    contest: Option<Contest>,
+    // Sorted by solved problems, most first.
+    languages: Vec<Language>,
+    tags: Vec<Tag>,
+    // The active badge first, then the others newest first.
+    badges: Vec<Badge>,
 }
 
 impl UserInfo {
@@ -222,22 +676,6 @@ impl UserInfo {
     }
 }
 
-impl Default for UserInfo {
-    fn default() -> Self {
-        UserInfo {
-            username: "thibault-cne".to_string(),
-            profile: Profile::default(),
-            submissions: vec![Problem {
-                difficulty: Difficulty::Easy,
-                count: 10,
-                total: 1000,
-                submissions: 100,
-            }],
-            streak: 50,
-        }
-    }
-}
-
 #[derive(Debug, Clone)]
 struct Profile {
     realname: String,
@@ -248,19 +686,84 @@ struct Profile {
     ranking: u32,
 }
 
-impl Default for Profile {
-    fn default() -> Self {
-        Profile {
-            realname: "Thibault Cheneviere".to_string(),
-            about: String::new(),
-            avatar: String::new(),
-            skills: Vec::new(),
-            country: Some("France".to_string()),
-            ranking: 810_207,
+#[derive(Debug, Clone, PartialEq)]
+struct Badge {
+    name: String,
+    // Absolute URL of the icon, inlined when the badge is drawn.
+    icon: String,
+    // Day the badge was earned, as `YYYY-MM-DD`.
+    earned: String,
+}
+
+#[derive(Debug, Clone)]
+struct RecentSubmission {
+    title: String,
+    title_slug: String,
+    // Unix timestamp, in seconds.
+    timestamp: i64,
+    // `None` for problems that no longer exist.
+    difficulty: Option<Difficulty>,
+}
+
+#[derive(Debug, Clone)]
+struct RecentSubmissions {
+    // Newest first.
+    submissions: Vec<RecentSubmission>,
+    // Unix timestamp of the request, submission times are relative to it.
+    fetched_at: i64,
+}
+
+#[derive(Debug, Clone)]
+struct Language {
+    name: String,
+    solved: u32,
+}
+
+#[derive(Debug, Clone)]
+struct Tag {
+    name: String,
+    level: TagLevel,
+    solved: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TagLevel {
+    Fundamental,
+    Intermediate,
+    Advanced,
+}
+
+impl TagLevel {
+    // Topics follow the colors of the difficulties.
+    fn color(&self) -> &'static str {
+        match self {
+            TagLevel::Fundamental => "var(--color-1)",
+            TagLevel::Intermediate => "var(--color-2)",
+            TagLevel::Advanced => "var(--color-3)",
         }
     }
 }
 
+#[derive(Debug, Clone)]
+struct Contest {
+    rating: f64,
+    global_ranking: u32,
+    total_participants: u32,
+    top_percentage: f64,
+    attended: u32,
+    // Attended contests, oldest first.
+    history: Vec<ContestResult>,
+}
+
+#[derive(Debug, Clone)]
+struct ContestResult {
+    title: String,
+    // Unix timestamp, in seconds.
+    start_time: i64,
+    rating: f64,
+    ranking: u32,
+}
+
 #[derive(Debug, Clone)]
 struct Problem {
     difficulty: Difficulty,
@@ -0,0 +1,667 @@
+// Golden-file tests for the rendered card.
+//
+// Every case renders a fixture through `Generator::hydrate` and compares the
+// SVG with `snapshots/<case>.svg`. On mismatch the new output is written next
+// to it as `<case>.svg.new` so both can be diffed or opened in a browser.
+// Accept the new output with:
+//
+//     RUSTME_BLESS=1 cargo test -p leetcode
+//
+// Fonts are fetched over the network and inlined as base64, so the cards are
+// drawn with the system font stack, which embeds nothing.
+
+use std::path::PathBuf;
+
+use core::{error::Error, font::FontStack, Extension as _};
+
+use crate::{
+    calendar::Calendar, extension, icon, layout, Badge, Config, Contest, ContestResult, Difficulty,
+    Generator, Language, Problem, Profile, RecentSubmission, RecentSubmissions, Tag, TagLevel,
+    UserInfo,
+};
+
+fn problem(difficulty: Difficulty, count: u32, total: u32) -> Problem {
+    Problem {
+        difficulty,
+        count,
+        total,
+        submissions: count * 2,
+    }
+}
+
+fn user_info(username: &str, ranking: u32, solved: [u32; 3]) -> UserInfo {
+    let totals = [800, 1600, 600];
+
+    UserInfo {
+        username: username.to_string(),
+        profile: Profile {
+            realname: String::new(),
+            about: String::new(),
+            avatar: String::new(),
+            skills: Vec::new(),
+            country: None,
+            ranking,
+        },
+        submissions: vec![
+            problem(Difficulty::All, solved.iter().sum(), totals.iter().sum()),
+            problem(Difficulty::Easy, solved[0], totals[0]),
+            problem(Difficulty::Medium, solved[1], totals[1]),
+            problem(Difficulty::Hard, solved[2], totals[2]),
+        ],
+        streak: 3,
+        calendar: Calendar::default(),
+        contest: None,
+        languages: Vec::new(),
+        tags: Vec::new(),
+        badges: Vec::new(),
+    }
+}
+
+fn skilled_user_info(username: &str) -> UserInfo {
+    let mut user_info = user_info(username, 123_456, [30, 20, 10]);
+    user_info.languages = [("Rust", 41), ("Python3", 17), ("C++", 9), ("TypeScript", 3)]
+        .map(|(name, solved)| Language {
+            name: name.to_string(),
+            solved,
+        })
+        .to_vec();
+    user_info.tags = [
+        ("Array", TagLevel::Fundamental, 45),
+        ("Hash Table", TagLevel::Intermediate, 28),
+        ("Dynamic Programming", TagLevel::Advanced, 21),
+        ("String", TagLevel::Fundamental, 19),
+        ("Backtracking", TagLevel::Advanced, 6),
+    ]
+    .map(|(name, level, solved)| Tag {
+        name: name.to_string(),
+        level,
+        solved,
+    })
+    .to_vec();
+    user_info
+}
+
+// Twenty contests every other week from 2023-06-04, climbing with a few
+// setbacks.
+fn contest() -> Contest {
+    let history = (0..20)
+        .map(|i| ContestResult {
+            title: format!("Weekly Contest {}", 348 + i),
+            start_time: 1_685_845_800 + i * 14 * 86_400,
+            rating: 1500.0 + 20.0 * i as f64 - [0.0, 45.0, 10.0, 70.0][i as usize % 4],
+            ranking: 9000 - 300 * i as u32,
+        })
+        .collect::<Vec<ContestResult>>();
+
+    Contest {
+        rating: history.last().unwrap().rating,
+        global_ranking: 52_817,
+        total_participants: 612_440,
+        top_percentage: 8.62,
+        attended: history.len() as u32,
+        history,
+    }
+}
+
+// A year of activity ending on 2024-06-15, busier on weekdays and with a
+// break in the winter.
+const TODAY: i64 = 19_889;
+
+fn calendar() -> Calendar {
+    let days = (TODAY - 400..=TODAY)
+        .filter(|day| !(19_710..19_740).contains(day))
+        .map(|day| {
+            (
+                day,
+                ((day * 7919) % 13) as u32 / (1 + (day % 7 == 3) as u32 * 4),
+            )
+        })
+        .filter(|(_, count)| *count > 0)
+        .collect::<std::collections::BTreeMap<i64, u32>>();
+
+    let active_days = days.len() as u32;
+    Calendar::new(days, vec![2023, 2024], active_days, TODAY)
+}
+
+fn active_user_info(username: &str) -> UserInfo {
+    let mut user_info = user_info(username, 123_456, [30, 20, 10]);
+    user_info.calendar = calendar();
+    user_info
+}
+
+// Accepted submissions fetched at noon on `TODAY`, the oldest of them on a
+// problem that has since been removed.
+fn recent_submissions() -> RecentSubmissions {
+    let now = TODAY * 86_400 + 12 * 3_600;
+    let submissions = [
+        ("Two Sum", "two-sum", 40, Some(Difficulty::Easy)),
+        (
+            "LRU Cache",
+            "lru-cache",
+            3 * 3_600,
+            Some(Difficulty::Medium),
+        ),
+        (
+            "Minimum Number of Operations to Make All Array Elements Equal to One",
+            "minimum-number-of-operations-to-make-all-array-elements-equal-to-one",
+            2 * 86_400,
+            Some(Difficulty::Hard),
+        ),
+        (
+            "Median of Two Sorted Arrays",
+            "median-of-two-sorted-arrays",
+            40 * 86_400,
+            Some(Difficulty::Hard),
+        ),
+        ("A Removed Problem", "a-removed-problem", 400 * 86_400, None),
+    ]
+    .map(|(title, slug, ago, difficulty)| RecentSubmission {
+        title: title.to_string(),
+        title_slug: slug.to_string(),
+        timestamp: now - ago,
+        difficulty,
+    });
+
+    RecentSubmissions {
+        submissions: submissions.to_vec(),
+        fetched_at: now,
+    }
+}
+
+// Eleven badges with icons already in the cache, but for the last one whose
+// URL cannot even be requested.
+fn badged_user_info(username: &str) -> UserInfo {
+    let colors = ["#ffa116", "#2cbb5d", "#ef4743"];
+    let mut badges = (0..10)
+        .map(|i| {
+            let icon = format!("https://leetcode.invalid/badges/{i}.svg");
+            let svg = format!(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 2 2"><circle cx="1" cy="1" r="1" fill="{}"/></svg>"#,
+                colors[i % 3]
+            );
+            icon::insert(
+                &icon,
+                &format!("data:image/svg+xml;base64,{}", base64_encode(&svg)),
+            );
+            Badge {
+                name: format!("Badge {i}"),
+                icon,
+                earned: format!("2024-{:02}-01", 12 - i),
+            }
+        })
+        .collect::<Vec<Badge>>();
+    badges.push(Badge {
+        name: "Broken".to_string(),
+        icon: "not a url".to_string(),
+        earned: "2023-01-01".to_string(),
+    });
+
+    let mut user_info = user_info(username, 123_456, [30, 20, 10]);
+    user_info.badges = badges;
+    user_info
+}
+
+fn base64_encode(text: &str) -> String {
+    use base64::Engine;
+    base64::engine::general_purpose::STANDARD.encode(text)
+}
+
+// Replaces every font of `config` that would be downloaded.
+fn offline(config: Config) -> Config {
+    let system = |stack: &FontStack| match stack.fonts().next() {
+        Some(_) => FontStack::system(),
+        None => stack.clone(),
+    };
+    let fonts = config.fonts.clone();
+
+    let config = config.set_font(system(&fonts.body));
+    let config = match fonts.heading {
+        Some(heading) => config.set_heading_font(system(&heading)),
+        None => config,
+    };
+    match fonts.number {
+        Some(number) => config.set_number_font(system(&number)),
+        None => config,
+    }
+}
+
+// `#[tokio::test]` expands to `::core::...` paths which resolve to our own
+// `core` crate, so the runtime is driven by hand.
+fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+        .block_on(future)
+}
+
+fn hydrate(config: Config, user_info: UserInfo) -> String {
+    let config = offline(config);
+    let extensions = config.get_extensions();
+
+    let mut generator = Generator::new(config);
+    generator.user_info = Some(user_info);
+    block_on(generator.hydrate_with(extensions)).unwrap()
+}
+
+// Hydrates a card listing the `limit` last submissions, as if they had been
+// fetched already.
+fn hydrate_recent(config: Config, user_info: UserInfo, limit: usize) -> String {
+    let config = offline(config.add_extension(extension::Extension::RecentSubmissions(limit)));
+    let extensions = config.get_extensions();
+
+    let mut generator = Generator::new(config);
+    generator.user_info = Some(user_info);
+    generator.recent = Some(recent_submissions());
+    block_on(generator.hydrate_with(extensions)).unwrap()
+}
+
+fn render_error(config: Config, error: Error) -> String {
+    let config = offline(config);
+    let extensions = config.get_error_extensions();
+
+    let mut generator = Generator::new(config);
+    let mut body = Vec::new();
+    let mut style = Vec::new();
+    for ext in extensions {
+        block_on(ext.extend(&mut generator, &mut body, &mut style)).unwrap();
+    }
+    generator.render_error(&error, body, &style)
+}
+
+fn assert_snapshot(name: &str, actual: &str) {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("snapshots");
+    let path = dir.join(format!("{name}.svg"));
+    let new_path = dir.join(format!("{name}.svg.new"));
+
+    std::fs::create_dir_all(&dir).unwrap();
+    if std::env::var_os("RUSTME_BLESS").is_some() {
+        std::fs::write(&path, actual).unwrap();
+        let _ = std::fs::remove_file(&new_path);
+        return;
+    }
+
+    match std::fs::read_to_string(&path) {
+        Ok(expected) if expected == actual => {
+            let _ = std::fs::remove_file(&new_path);
+        }
+        expected => {
+            std::fs::write(&new_path, actual).unwrap();
+            let position = expected
+                .as_ref()
+                .map(|expected| {
+                    expected
+                        .bytes()
+                        .zip(actual.bytes())
+                        .position(|(a, b)| a != b)
+                        .unwrap_or(expected.len().min(actual.len()))
+                })
+                .unwrap_or(0);
+            panic!(
+                "snapshot `{name}` does not match (first difference at byte {position}), \
+                 review {} and accept it with `RUSTME_BLESS=1 cargo test -p leetcode`",
+                new_path.display()
+            );
+        }
+    }
+}
+
+macro_rules! snapshots {
+    ($($name:ident => $config:expr, $user_info:expr;)*) => {
+        $(
+            #[test]
+            fn $name() {
+                assert_snapshot(stringify!($name), &hydrate($config, $user_info));
+            }
+        )*
+    };
+}
+
+snapshots! {
+    default_card => Config::new("fixture"), user_info("fixture", 123_456, [30, 20, 10]);
+    zero_solved => Config::new("newcomer"), user_info("newcomer", 5_000_000, [0, 0, 0]);
+    everything_solved => Config::new("completionist"), user_info("completionist", 1, [800, 1600, 600]);
+    huge_ranking => Config::new("unranked"), user_info("unranked", u32::MAX, [1, 0, 0]);
+    unicode_name => Config::new("名前"), user_info("ユーザー名-ñandú-🦀 <&>", 42, [12, 7, 3]);
+    single_theme_without_animation => Config::new("fixture")
+        .set_single_theme("ferrari".into())
+        .set_animation(false), user_info("fixture", 123_456, [30, 20, 10]);
+    custom_palette => Config::new("fixture")
+        .set_single_theme("dark".into())
+        .add_colors(core::theme::parse_palette("bg-0:1a1b26,text-0:c0caf5,color-0:ff9e64").unwrap())
+        .set_animation(false), user_info("fixture", 123_456, [30, 20, 10]);
+    gradient_theme => Config::new("fixture")
+        .set_single_theme("aurora".into())
+        .set_animation(false), user_info("fixture", 123_456, [30, 20, 10]);
+    pattern_palette => Config::new("fixture")
+        .set_single_theme("light".into())
+        .add_colors(core::theme::parse_palette("bg-0:dots(fff,eef1f5),color-0:linear-gradient(90deg,ffa116,d9534f)").unwrap())
+        .set_animation(false), user_info("fixture", 123_456, [30, 20, 10]);
+    gradient_on_progress_bars => Config::new("fixture")
+        .set_single_theme("light".into())
+        .add_colors(core::theme::parse_palette("color-1:linear-gradient(90deg,2cbb5d,ffa116)").unwrap())
+        .set_animation(false), user_info("fixture", 123_456, [300, 20, 10]);
+    derived_light_variant => {
+        let (light, dark) = core::theme::pair("ferrari").unwrap();
+        Config::new("fixture").set_light_theme(light).set_dark_theme(dark).set_animation(false)
+    }, user_info("fixture", 123_456, [30, 20, 10]);
+    dark_palette_over_both_themes => Config::new("fixture")
+        .add_colors(core::theme::parse_palette("bg-0:1a1b26,text-0:c0caf5,text-1:a9b1d6").unwrap())
+        .set_animation(false), user_info("fixture", 123_456, [30, 20, 10]);
+    long_username => Config::new("fixture").set_animation(false),
+        user_info("an-unusually-long-leetcode-username-2024", 1_234_567, [30, 20, 10]);
+    long_wide_username => Config::new("fixture").set_animation(false),
+        user_info("とても長いユーザー名ですよ本当に", 123_456, [30, 20, 10]);
+    heading_and_number_fonts => Config::new("fixture")
+        .set_font(FontStack::parse("serif").unwrap())
+        .set_heading_font(FontStack::parse("system").unwrap())
+        .set_number_font(FontStack::parse("ui-monospace,monospace").unwrap())
+        .set_animation(false), user_info("fixture", 123_456, [30, 20, 10]);
+    wide_card => Config::new("fixture").set_width(700).set_height(260),
+        user_info("fixture", 123_456, [30, 20, 10]);
+    smallest_card => {
+        let (width, height) = layout::Kind::Default.min_size();
+        Config::new("fixture").set_width(width).set_height(height).set_animation(false)
+    }, user_info("an-unusually-long-leetcode-username-2024", 1_234_567, [30, 20, 10]);
+    wide_layout => Config::new("fixture").set_layout(layout::Kind::Wide), {
+        let mut user_info = user_info("fixture", 123_456, [30, 20, 10]);
+        user_info.profile.country = Some("France".to_string());
+        user_info
+    };
+    compact_layout => Config::new("fixture")
+        .set_layout(layout::Kind::Compact)
+        .set_animation(false), user_info("fixture", 123_456, [30, 20, 10]);
+    heatmap_layout => Config::new("fixture")
+        .set_layout(layout::Kind::Heatmap)
+        .set_animation(false), active_user_info("fixture");
+    contest_layout => Config::new("fixture").set_layout(layout::Kind::Contest), {
+        let mut user_info = user_info("fixture", 123_456, [30, 20, 10]);
+        user_info.contest = Some(contest());
+        user_info
+    };
+    contest_layout_single_contest => Config::new("fixture")
+        .set_layout(layout::Kind::Contest)
+        .set_animation(false), {
+        let mut user_info = user_info("fixture", 123_456, [30, 20, 10]);
+        let mut contest = contest();
+        contest.history.truncate(1);
+        contest.rating = contest.history[0].rating;
+        contest.attended = 1;
+        user_info.contest = Some(contest);
+        user_info
+    };
+    contest_layout_without_contests => Config::new("newcomer")
+        .set_layout(layout::Kind::Contest)
+        .set_animation(false), user_info("newcomer", 5_000_000, [0, 0, 0]);
+    skills_layout => Config::new("fixture").set_layout(layout::Kind::Skills), skilled_user_info("fixture");
+    skills_layout_limit => Config::new("fixture")
+        .set_layout(layout::Kind::Skills)
+        .set_limit(5)
+        .set_animation(false), skilled_user_info("fixture");
+    skills_layout_narrow => Config::new("fixture")
+        .set_layout(layout::Kind::Skills)
+        .set_width(380)
+        .set_animation(false), skilled_user_info("fixture");
+    skills_layout_empty => Config::new("newcomer")
+        .set_layout(layout::Kind::Skills)
+        .set_animation(false), user_info("newcomer", 5_000_000, [0, 0, 0]);
+    heatmap_below_the_card => Config::new("fixture").set_heatmap(true), active_user_info("fixture");
+    heatmap_without_submissions => Config::new("newcomer")
+        .set_layout(layout::Kind::Heatmap)
+        .set_animation(false), {
+        let mut user_info = user_info("newcomer", 5_000_000, [0, 0, 0]);
+        user_info.calendar = Calendar::new(Default::default(), Vec::new(), 0, TODAY);
+        user_info
+    };
+    compact_layout_long_username => Config::new("fixture")
+        .set_layout(layout::Kind::Compact)
+        .set_animation(false), user_info("an-unusually-long-leetcode-username-2024", 1_234_567, [800, 1600, 600]);
+}
+
+#[test]
+fn error_invalid_parameter() {
+    let error =
+        Error::new_invalid_parameter().with(r#"width must be a positive integer, got "<wide>""#);
+    assert_snapshot(
+        "error_invalid_parameter",
+        &render_error(Config::new("fixture"), error),
+    );
+}
+
+#[test]
+fn error_long_hint() {
+    let error = layout::Kind::try_from("tall").unwrap_err();
+    assert_snapshot(
+        "error_long_hint",
+        &render_error(Config::new("fixture"), error),
+    );
+}
+
+#[test]
+fn error_leetcode_unavailable() {
+    assert_snapshot(
+        "error_leetcode_unavailable",
+        &render_error(
+            Config::new("fixture").set_single_theme("dark".into()),
+            Error::new_reqwest_default().with("connection refused"),
+        ),
+    );
+}
+
+#[test]
+fn cards_below_the_minimum_size_are_rejected() {
+    let config = offline(Config::new("fixture").set_width(200));
+    let mut generator = Generator::new(config);
+    generator.user_info = Some(user_info("fixture", 123_456, [30, 20, 10]));
+
+    let error = block_on(generator.hydrate_with([])).unwrap_err();
+    assert_eq!(error.hint(), "width must be at least 380 pixels, got 200");
+
+    // The error card falls back to the default size.
+    let svg = generator.render_error(&error, Vec::new(), &[]);
+    assert!(svg.contains(r#"width="500""#) && svg.contains(r#"height="200""#));
+}
+
+#[test]
+fn skills_cards_are_tall_enough_for_their_rows() {
+    let config = Config::new("fixture")
+        .set_layout(layout::Kind::Skills)
+        .set_limit(10);
+    assert_eq!(config.layout().unwrap().height(), 410);
+
+    let error = config.set_height(170).layout().unwrap_err();
+    assert_eq!(error.hint(), "height must be at least 380 pixels, got 170");
+
+    // The limit is bounded before the card grows with it.
+    let config = Config::new("fixture")
+        .set_layout(layout::Kind::Skills)
+        .set_limit(usize::MAX);
+    assert_eq!(config.layout().unwrap().height(), 410);
+}
+
+#[test]
+fn only_the_drawn_sections_are_fetched() {
+    let sections = |config: Config| config.sections().unwrap();
+
+    assert_eq!(
+        sections(Config::new("fixture")),
+        crate::graphql::Sections::default()
+    );
+    assert_eq!(
+        sections(Config::new("fixture").set_heatmap(true)),
+        crate::graphql::Sections {
+            calendar: true,
+            ..Default::default()
+        }
+    );
+    assert_eq!(
+        sections(Config::new("fixture").set_layout(layout::Kind::Contest)),
+        crate::graphql::Sections {
+            contest: true,
+            ..Default::default()
+        }
+    );
+    assert_eq!(
+        sections(Config::new("fixture").set_layout(layout::Kind::Skills)),
+        crate::graphql::Sections {
+            skills: true,
+            ..Default::default()
+        }
+    );
+    let badges = Config::new("fixture").add_extension(extension::Extension::Badges(5));
+    assert!(sections(badges.clone()).badges);
+    // A badge has no room for the row of badges.
+    assert!(!sections(badges.set_layout(layout::Kind::Compact)).badges);
+}
+
+#[test]
+fn error_compact_layout() {
+    assert_snapshot(
+        "error_compact_layout",
+        &render_error(
+            Config::new("fixture").set_layout(layout::Kind::Compact),
+            Error::new_invalid_parameter().with("username is missing"),
+        ),
+    );
+}
+
+// Every variable read by the card, including the theme stylesheet, must be
+// defined by every registered theme.
+#[test]
+fn themes_define_every_variable_used_by_the_card() {
+    let card = hydrate(
+        Config::new("fixture"),
+        user_info("fixture", 123_456, [30, 20, 10]),
+    );
+    let used = card
+        .split("var(")
+        .skip(1)
+        .filter_map(|rest| rest.split(')').next())
+        .filter(|name| !name.starts_with("--font-"))
+        .collect::<std::collections::BTreeSet<&str>>();
+    assert!(used.contains("--color-3"));
+
+    for info in core::theme::catalog() {
+        let theme = info.theme();
+        for name in &used {
+            assert!(theme.color(name).is_some(), "{} misses {name}", info.name);
+        }
+    }
+}
+
+#[test]
+fn recent_submissions_below_the_card() {
+    assert_snapshot(
+        "recent_submissions",
+        &hydrate_recent(
+            Config::new("fixture"),
+            user_info("fixture", 123_456, [30, 20, 10]),
+            5,
+        ),
+    );
+    assert_snapshot(
+        "recent_submissions_below_the_heatmap",
+        &hydrate_recent(
+            Config::new("fixture")
+                .set_layout(layout::Kind::Wide)
+                .set_heatmap(true),
+            active_user_info("fixture"),
+            3,
+        ),
+    );
+}
+
+#[test]
+fn gradients_span_the_appended_sections() {
+    let config = Config::new("fixture")
+        .set_single_theme("light".into())
+        .add_colors(core::theme::parse_palette("bg-0:linear-gradient(180deg,fff,eef1f5)").unwrap());
+    let svg = hydrate_recent(config, user_info("fixture", 123_456, [30, 20, 10]), 5);
+
+    let height = svg
+        .split(r#"height=""#)
+        .nth(1)
+        .unwrap()
+        .split('"')
+        .next()
+        .unwrap();
+    assert_ne!(height, "200");
+    assert!(svg.contains(&format!(r#"y2="{height}""#)), "{svg}");
+}
+
+#[test]
+fn compact_layout_has_no_room_for_recent_submissions() {
+    let config = Config::new("fixture").set_layout(layout::Kind::Compact);
+    let user_info = user_info("fixture", 123_456, [30, 20, 10]);
+
+    assert_eq!(
+        hydrate_recent(config.clone(), user_info.clone(), 5),
+        hydrate(config, user_info),
+    );
+}
+
+#[test]
+fn badges_below_the_card() {
+    let config = |limit| Config::new("fixture").add_extension(extension::Extension::Badges(limit));
+    let mut user_info = badged_user_info("fixture");
+
+    assert_snapshot("badges", &hydrate(config(20), user_info.clone()));
+    user_info.badges.drain(..8);
+    assert_snapshot(
+        "badges_broken_icon",
+        &hydrate(config(20), user_info.clone()),
+    );
+    user_info.badges.clear();
+    assert_snapshot("badges_none_earned", &hydrate(config(5), user_info));
+}
+
+#[test]
+fn compact_layout_has_no_room_for_badges() {
+    let config = Config::new("fixture").set_layout(layout::Kind::Compact);
+    let user_info = badged_user_info("fixture");
+
+    assert_eq!(
+        hydrate(
+            config
+                .clone()
+                .add_extension(extension::Extension::Badges(5)),
+            user_info.clone()
+        ),
+        hydrate(config, user_info),
+    );
+}
+
+#[test]
+fn identical_inputs_produce_identical_bytes() {
+    let render = || {
+        hydrate(
+            Config::new("fixture"),
+            user_info("fixture", 123_456, [30, 20, 10]),
+        )
+    };
+    let first = render();
+
+    for _ in 0..8 {
+        assert_eq!(render(), first);
+    }
+}
+
+// A font shared by several roles is embedded once, with the glyphs of all of
+// them.
+#[test]
+fn fonts_shared_by_roles_are_embedded_once() {
+    let ttf = include_bytes!("../../../font/Formula1-Regular-1.ttf");
+    let font = core::font::JsonFont::from_ttf("Formula 1", ttf);
+    let config = Config::new("fixture").set_animation(false);
+    let extensions = offline(config.clone()).get_extensions();
+
+    let mut generator = Generator::new(config);
+    generator.user_info = Some(user_info("fixture", 123_456, [30, 20, 10]));
+    generator.font_faces.push((
+        std::sync::Arc::new(font),
+        vec![None, Some(crate::font::HEADING.to_string())],
+    ));
+    let card = block_on(generator.hydrate_with(extensions)).unwrap();
+
+    assert_eq!(card.matches("@font-face").count(), 1);
+    assert!(card.len() < ttf.len(), "{} bytes", card.len());
+}
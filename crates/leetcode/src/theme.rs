@@ -1,15 +1,18 @@
-pub use core::theme::{DARK, FERRARI, LIGHT};
-use core::{error::Result, minimize_css, theme::Theme, Extension};
+use core::{error::Result, item::Item, minimize_css, theme::Theme, Extension};
 
 use crate::Generator;
 
 impl Extension<Generator> for Theme {
     async fn extend(
         &self,
-        _: &mut Generator,
-        _: &mut Vec<core::item::Item>,
+        generator: &mut Generator,
+        _: &mut Vec<Item>,
         style: &mut Vec<String>,
     ) -> Result<()> {
+        // Gradients and patterns span the whole card, they are defined once
+        // the sections appended below it are known.
+        generator.themes.push(self.clone());
+
         let vars = self.format_vars();
         let (start, end) =
             if self.prefered_color_scheme == "dark" || self.prefered_color_scheme == "light" {
@@ -28,7 +31,7 @@ impl Extension<Generator> for Theme {
             .fold(start, |acc, var| format!("{}{}", acc, var,));
         theme.push('}');
         theme.push_str(DEFAULT_STR);
-        theme.push_str(self.style);
+        theme.push_str(&self.style);
         theme.push(end);
         style.push(minimize_css(&theme).to_string());
         Ok(())
@@ -1,97 +1,237 @@
 use codegen::handler;
 use worker::*;
 
-use core::font::Font;
-use core::theme::Theme;
-use core::Generator;
+use core::error::Error;
+use core::font::{self, FontStack};
+use core::raster::Format;
+use core::theme::{self, ColorScheme, Theme, Variable};
 
 use leetcode::extension::Extension;
-use leetcode::Config;
+use leetcode::layout::Kind;
+use leetcode::{Config, MAX_LIMIT};
 
 pub enum QueryParams {
     Username(String),
     Width(u32),
     Height(u32),
-    Font(Font),
-    Themes(Vec<Theme>),
-    Extension(String),
+    Layout(Kind),
+    Heatmap(bool),
+    Limit(usize),
+    Font(FontStack),
+    HeadingFont(FontStack),
+    NumberFont(FontStack),
+    Themes(Vec<String>),
+    Colors(Vec<Variable>),
+    Extension(Extension),
     Animation(bool),
+    Format(Format),
+    ColorScheme(ColorScheme),
 }
 
 #[handler(leetcode)]
 pub async fn leetcode_handler(req: Request, _: &RouteContext<super::Caches>) -> Result<Response> {
-    let config = match crate::leetcode::config_from_url(&req.url().unwrap()) {
-        Some(config) => config,
-        None => return Response::error("Invalid query parameters", 400),
+    let (config, error) = crate::leetcode::config_from_url(&req.url()?);
+    let format = config.format();
+
+    let result = match error {
+        Some(error) => Err(error),
+        None => {
+            let mut generator = leetcode::Generator::new(config.clone());
+            generator.verbose();
+            generator.generate_bytes().await
+        }
     };
 
-    let mut generator = leetcode::Generator::new(config);
-    generator.verbose();
-    match generator.generate().await {
-        Ok(html) => Response::from_html(html),
-        Err(e) => Response::error(e.to_string(), 500),
+    match result {
+        Ok(bytes) => image_response(bytes, format, 200),
+        Err(error) => {
+            let status = error.status();
+            let mut generator = leetcode::Generator::new(config);
+            generator.verbose();
+            match generator.generate_error(&error).await {
+                Ok(bytes) => image_response(bytes, format, status),
+                Err(_) => Response::error(error.to_string(), status),
+            }
+        }
     }
 }
 
-fn parse_query(query: &Url) -> Vec<QueryParams> {
+pub fn themes_handler(_: Request, _: RouteContext<super::Caches>) -> Result<Response> {
+    Response::from_json(&theme::catalog())
+}
+
+pub fn fonts_handler(_: Request, _: RouteContext<super::Caches>) -> Result<Response> {
+    Response::from_json(&font::catalog())
+}
+
+fn image_response(bytes: Vec<u8>, format: Format, status: u16) -> Result<Response> {
+    let mut resp = Response::from_bytes(bytes)?.with_status(status);
+    resp.headers_mut()
+        .set("Content-Type", format.content_type())?;
+    Ok(resp)
+}
+
+fn invalid_parameter(key: &str, value: &str, expected: &str) -> Error {
+    Error::new_invalid_parameter().with(format!("{key} must be {expected}, got \"{value}\""))
+}
+
+fn parse_query(query: &Url) -> Vec<std::result::Result<QueryParams, Error>> {
     query
         .query_pairs()
         .flat_map(|(key, value)| {
             let key = key.to_ascii_lowercase();
 
             match key.as_str() {
-                "username" => Some(QueryParams::Username(value.to_string())),
-                "width" => Some(QueryParams::Width(value.parse().unwrap())),
-                "height" => Some(QueryParams::Height(value.parse().unwrap())),
-                "font" => Some(QueryParams::Font(value.to_string().into())),
-                "theme" => {
-                    let themes = value.split(',').map(Theme::from).take(2).collect();
-                    Some(QueryParams::Themes(themes))
-                }
-                "animation" => value.parse::<bool>().ok().map(QueryParams::Animation),
-                "ext" => Some(QueryParams::Extension(value.to_string())),
+                "username" => Some(Ok(QueryParams::Username(value.to_string()))),
+                "width" => Some(
+                    value
+                        .parse()
+                        .map(QueryParams::Width)
+                        .map_err(|_| invalid_parameter("width", &value, "a positive integer")),
+                ),
+                "height" => Some(
+                    value
+                        .parse()
+                        .map(QueryParams::Height)
+                        .map_err(|_| invalid_parameter("height", &value, "a positive integer")),
+                ),
+                "layout" => Some(Kind::try_from(&*value).map(QueryParams::Layout)),
+                "heatmap" => Some(
+                    value
+                        .parse::<bool>()
+                        .map(QueryParams::Heatmap)
+                        .map_err(|_| invalid_parameter("heatmap", &value, "true or false")),
+                ),
+                "limit" => Some(
+                    value
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|limit| (1..=MAX_LIMIT).contains(limit))
+                        .map(QueryParams::Limit)
+                        .ok_or_else(|| {
+                            let expected = format!("an integer from 1 to {MAX_LIMIT}");
+                            invalid_parameter("limit", &value, &expected)
+                        }),
+                ),
+                "font" => Some(FontStack::parse(&value).map(QueryParams::Font)),
+                "heading-font" => Some(FontStack::parse(&value).map(QueryParams::HeadingFont)),
+                "number-font" => Some(FontStack::parse(&value).map(QueryParams::NumberFont)),
+                "theme" => Some(
+                    value
+                        .split(',')
+                        .take(2)
+                        .map(|name| match theme::get(name) {
+                            Some(_) => Ok(name.to_string()),
+                            None => Err(invalid_parameter(
+                                "theme",
+                                name,
+                                "a theme listed at /themes",
+                            )),
+                        })
+                        .collect::<std::result::Result<Vec<String>, Error>>()
+                        .map(QueryParams::Themes),
+                ),
+                "animation" => Some(
+                    value
+                        .parse::<bool>()
+                        .map(QueryParams::Animation)
+                        .map_err(|_| invalid_parameter("animation", &value, "true or false")),
+                ),
+                "format" => Some(
+                    Format::try_from(&*value)
+                        .map(QueryParams::Format)
+                        .map_err(|e| e.with(format!("format \"{value}\" is not supported"))),
+                ),
+                "scheme" => Some(ColorScheme::try_from(&*value).map(QueryParams::ColorScheme)),
+                "palette" => Some(theme::parse_palette(&value).map(QueryParams::Colors)),
+                "ext" => extension(&value).map(|ext| ext.map(QueryParams::Extension)),
+                // Single color overrides, e.g. `bg-0=1a1b26` or `--color-1=green`.
+                color if theme::is_variable(color) => Some(
+                    Variable::parse(color, &value).map(|color| QueryParams::Colors(vec![color])),
+                ),
                 _ => None,
             }
         })
         .collect()
 }
 
-fn config_from_url(query: &Url) -> Option<Config> {
-    let params = parse_query(query);
+// Invalid parameters are skipped so that the error card can still be rendered
+// with the valid ones, the first error is returned alongside.
+fn config_from_url(query: &Url) -> (Config, Option<Error>) {
+    let mut error = None;
 
-    let config = params
+    let config = parse_query(query)
         .into_iter()
+        .filter_map(|param| match param {
+            Ok(param) => Some(param),
+            Err(e) => {
+                error.get_or_insert(e);
+                None
+            }
+        })
         .fold(Config::default(), |config, param| match param {
             QueryParams::Username(username) => config.set_username(&username),
             QueryParams::Width(width) => config.set_width(width),
             QueryParams::Height(height) => config.set_height(height),
+            QueryParams::Layout(layout) => config.set_layout(layout),
+            QueryParams::Heatmap(heatmap) => config.set_heatmap(heatmap),
+            QueryParams::Limit(limit) => config.set_limit(limit),
             QueryParams::Font(font) => config.set_font(font),
-            QueryParams::Themes(themes) if themes.len() == 1 => {
-                config.set_single_theme(themes.into_iter().next().unwrap())
-            }
-            QueryParams::Themes(themes) => {
-                let mut themes = themes.into_iter();
-                config
-                    .set_light_theme(themes.next().unwrap())
-                    .set_dark_theme(themes.next().unwrap())
-            }
+            QueryParams::HeadingFont(font) => config.set_heading_font(font),
+            QueryParams::NumberFont(font) => config.set_number_font(font),
+            // A single paired theme follows `prefers-color-scheme` with its pair.
+            QueryParams::Themes(themes) if themes.len() == 1 => match theme::pair(&themes[0]) {
+                Some((light, dark)) => config.set_light_theme(light).set_dark_theme(dark),
+                None => config.set_single_theme(Theme::from(themes[0].as_str())),
+            },
+            QueryParams::Themes(themes) => config
+                .set_light_theme(Theme::from(themes[0].as_str()))
+                .set_dark_theme(Theme::from(themes[1].as_str())),
+            QueryParams::Colors(colors) => config.add_colors(colors),
             QueryParams::Animation(animation) => config.set_animation(animation),
-            QueryParams::Extension(ext) => {
-                if let Some(ext) = extension(&ext) {
-                    config.add_extension(ext)
-                } else {
-                    config
-                }
-            }
+            QueryParams::Format(format) => config.set_format(format),
+            QueryParams::ColorScheme(scheme) => config.set_color_scheme(scheme),
+            QueryParams::Extension(ext) => config.add_extension(ext),
         });
 
-    if config.as_username() {
-        Some(config)
-    } else {
-        None
+    if !config.as_username() {
+        error.get_or_insert(Error::new_invalid_parameter().with("username is missing"));
     }
+
+    (config, error)
 }
 
-fn extension(_ext: &str) -> Option<Extension> {
-    None
+// Submissions listed by `ext=recent` unless given as `recent:N`.
+const RECENT_SUBMISSIONS: usize = 5;
+// Badges drawn by `ext=badges` unless given as `badges:N`, the row may fit
+// fewer of them.
+const BADGES: usize = 10;
+
+// Unknown extensions are ignored, a known one with an invalid count is an
+// error.
+fn extension(ext: &str) -> Option<std::result::Result<Extension, Error>> {
+    let (name, arg) = match ext.split_once(':') {
+        Some((name, arg)) => (name, Some(arg)),
+        None => (ext, None),
+    };
+    let (extension, default): (fn(usize) -> Extension, usize) = match name {
+        "recent" => (Extension::RecentSubmissions, RECENT_SUBMISSIONS),
+        "badges" => (Extension::Badges, BADGES),
+        _ => return None,
+    };
+    Some(match arg {
+        None => Ok(extension(default)),
+        Some(limit) => limit
+            .parse::<usize>()
+            .ok()
+            .filter(|limit| (1..=20).contains(limit))
+            .map(extension)
+            .ok_or_else(|| {
+                invalid_parameter(
+                    "ext",
+                    ext,
+                    &format!("{name} or {name}:N with N from 1 to 20"),
+                )
+            }),
+    })
 }
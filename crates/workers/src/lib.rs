@@ -26,6 +26,8 @@ pub async fn main(req: Request, env: Env, _ctx: worker::Context) -> Result<Respo
 
     Router::with_data(caches)
         .get_async("/leetcode", leetcode::leetcode_handler)
+        .get("/themes", leetcode::themes_handler)
+        .get("/fonts", leetcode::fonts_handler)
         .get("/", |_, _| Response::ok("up and running!"))
         .run(req, env)
         .await